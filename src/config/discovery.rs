@@ -0,0 +1,25 @@
+use std::path::{Path, PathBuf};
+
+/// Arquivos de configuração procurados quando `--config` não é informado, em ordem
+/// de preferência
+pub const DEFAULT_FILES: &[&str] = &["tasks.toml", "tasks.yaml", "tasks.yml", "tasks.json"];
+
+/// Variável de ambiente com o caminho do arquivo de configuração
+pub const CONFIG_ENV_VAR: &str = "RUNNER_CONFIG";
+
+/// Procura um dos `DEFAULT_FILES` em `start` e nos diretórios acima dele, parando na raiz do
+/// repositório git (o diretório que contém `.git`) ou na raiz do sistema de arquivos
+pub(crate) fn find_config(start: &Path) -> Option<PathBuf> {
+    for dir in start.ancestors() {
+        let found = DEFAULT_FILES.iter().map(|name| dir.join(name)).find(|path| path.is_file());
+        if found.is_some() {
+            return found;
+        }
+
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+
+    None
+}
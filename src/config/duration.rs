@@ -0,0 +1,33 @@
+use std::time::Duration;
+use toml::Value;
+
+/// Interpreta uma duração da configuração: um número de segundos (`30`) ou um texto
+/// com unidade (`"500ms"`, `"2s"`, `"5m"`, `"1h"`, `"1.5s"`)
+pub(crate) fn parse_duration(value: &Value) -> Option<Duration> {
+    match value {
+        Value::Integer(seconds) => u64::try_from(*seconds).ok().map(Duration::from_secs),
+        Value::Float(seconds) => Duration::try_from_secs_f64(*seconds).ok(),
+        Value::String(text) => parse_duration_str(text),
+        _ => None,
+    }
+}
+
+/// Interpreta uma duração informada como texto (ex: `--timeout 5m`)
+pub fn parse_duration_str(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    let unit_start = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(unit_start);
+    let number: f64 = number.parse().ok()?;
+
+    let seconds = match unit.trim() {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        _ => return None,
+    };
+
+    Duration::try_from_secs_f64(seconds).ok()
+}
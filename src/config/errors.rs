@@ -1,16 +1,80 @@
 use core::fmt;
 
+use super::{Location, discovery};
+
 #[derive(Debug)]
 pub enum ConfigError {
   ConfigNotFoundError(String),
-  ParsingError(String)
+  ConfigNotDiscovered(String),
+  ParsingError(Location, String),
+  UnsupportedFormat(String),
+  InvalidTaskField(String, String, String, Option<Box<Location>>),
+  DependencyNotFound(String, String),
+  DependencyCycle(Vec<String>),
+  InvalidOverride(String),
+  EnvVarNotSet(String, String, String, String, Option<Box<Location>>),
+  UnknownReference(String, String, Option<Box<Location>>),
+  ReferenceCycle(Vec<String>),
+  InvalidInclude(String),
+  IncludeNotFound(String, String),
+  IncludeCycle(Vec<String>),
+  ProfileNotFound(String, Vec<String>),
 }
 
 impl fmt::Display for ConfigError {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match *self {
       Self::ConfigNotFoundError(ref path ) => write!(f, "Configuração não encontrada no caminho '{}'", path),
-      Self::ParsingError(ref error) => write!(f, "Erro ao ler arquivo de configuraçao:\n{}", error)
+      Self::ConfigNotDiscovered(ref dir) => write!(f, "Nenhum arquivo de configuração ({}) encontrado em '{}' nem nos diretórios acima. Use --config ou a variável {}", discovery::DEFAULT_FILES.join(", "), dir, discovery::CONFIG_ENV_VAR),
+      Self::ParsingError(ref location, ref error) => {
+        write!(f, "Erro ao ler arquivo de configuração em {}: {}", location, error)?;
+        if let Some(snippet) = location.snippet() {
+          write!(f, "\n{}", snippet)?;
+        }
+        Ok(())
+      }
+      Self::UnsupportedFormat(ref path) => write!(f, "Formato de configuração não suportado: '{}'. Use .toml, .yaml, .yml ou .json", path),
+      Self::InvalidTaskField(ref task, ref field, ref expected, ref location) => {
+        write!(f, "Campo '{}' da task '{}' é inválido. Esperado: {}", field, task, expected)?;
+        write_origin(f, location)
+      }
+      Self::DependencyNotFound(ref task, ref dependency) => write!(f, "Task '{}' depende de '{}', que não está configurada", task, dependency),
+      Self::DependencyCycle(ref cycle) => write!(f, "Dependência circular entre tasks: {}", cycle.join(" -> ")),
+      Self::EnvVarNotSet(ref task, ref key, ref variable, ref message, ref location) => {
+        write!(f, "Variável de ambiente '{}' não definida (task '{}', chave '{}')", variable, task, key)?;
+        if !message.is_empty() {
+          write!(f, ": {}", message)?;
+        }
+        write_origin(f, location)
+      }
+      Self::UnknownReference(ref owner, ref reference, ref location) => {
+        write!(f, "Referência desconhecida '{{{{ {} }}}}' em '{}'", reference, owner)?;
+        write_origin(f, location)
+      }
+      Self::ReferenceCycle(ref cycle) => write!(f, "Referência circular entre valores: {}", cycle.join(" -> ")),
+      Self::InvalidInclude(ref file) => write!(f, "Campo 'include' de '{}' é inválido. Esperado: um array de caminhos de arquivos", file),
+      Self::IncludeNotFound(ref file, ref includer) => write!(f, "Arquivo '{}', incluído por '{}', não encontrado", file, includer),
+      Self::IncludeCycle(ref cycle) => write!(f, "Inclusão circular entre arquivos: {}", cycle.join(" -> ")),
+      Self::ProfileNotFound(ref profile, ref available) => {
+        write!(f, "Perfil '{}' não encontrado. ", profile)?;
+        if available.is_empty() {
+          write!(f, "Nenhum perfil está definido na configuração")
+        } else {
+          write!(f, "Perfis disponíveis: {}", available.join(", "))
+        }
+      }
+      Self::InvalidOverride(ref assignment) => write!(f, "Atribuição inválida '{}'. Use o formato chave=valor", assignment),
     }
   }
-}
\ No newline at end of file
+}
+
+/// Indica a posição de origem do valor (arquivo:linha:coluna) e o trecho do arquivo, se conhecidos
+fn write_origin(f: &mut fmt::Formatter, location: &Option<Box<Location>>) -> fmt::Result {
+  let Some(location) = location else { return Ok(()) };
+
+  write!(f, "\n  --> {}", location)?;
+  if let Some(snippet) = location.snippet() {
+    write!(f, "\n{}", snippet)?;
+  }
+  Ok(())
+}
@@ -0,0 +1,134 @@
+use super::{ConfigError, Location, location};
+use std::path::Path;
+use toml::Value;
+
+/// Formato de um arquivo de configuração, escolhido pela extensão
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Format {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl Format {
+    pub fn from_path(path: &Path) -> Result<Self, ConfigError> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Ok(Self::Toml),
+            Some("yaml") | Some("yml") => Ok(Self::Yaml),
+            Some("json") => Ok(Self::Json),
+            _ => Err(ConfigError::UnsupportedFormat(path.display().to_string())),
+        }
+    }
+
+    /// Interpreta `content` e converte o resultado para o modelo de valores do TOML,
+    /// usado pelo restante da configuração
+    pub fn parse(self, content: &str, file: &str) -> Result<toml::Table, ConfigError> {
+        let value = match self {
+            Self::Toml => {
+                return toml::from_str(content).map_err(|err| {
+                    let span = err.span().unwrap_or(0..0);
+                    ConfigError::ParsingError(
+                        location::span_location(file, content, span),
+                        err.message().to_string(),
+                    )
+                });
+            }
+            Self::Yaml => {
+                let value: serde_yaml_ng::Value = serde_yaml_ng::from_str(content).map_err(|err| {
+                    let (line, column) = err.location().map(|l| (l.line(), l.column())).unwrap_or((1, 1));
+                    parsing_error(file, content, line, column, err.to_string())
+                })?;
+                from_yaml(value)
+            }
+            Self::Json => {
+                let value: serde_json::Value = serde_json::from_str(content)
+                    .map_err(|err| parsing_error(file, content, err.line(), err.column(), err.to_string()))?;
+                from_json(value)
+            }
+        };
+
+        match value {
+            Some(Value::Table(table)) => Ok(table),
+            // Um arquivo vazio equivale a uma configuração sem tasks
+            None => Ok(toml::Table::new()),
+            Some(_) => Err(parsing_error(
+                file,
+                content,
+                1,
+                1,
+                "o documento deve ser um objeto/mapa".to_string(),
+            )),
+        }
+    }
+}
+
+/// Erro de leitura na posição indicada, sem a posição repetida ao final da mensagem
+fn parsing_error(file: &str, content: &str, line: usize, column: usize, message: String) -> ConfigError {
+    let suffix = format!(" at line {} column {}", line, column);
+    let message = message.strip_suffix(&suffix).unwrap_or(&message).to_string();
+
+    ConfigError::ParsingError(
+        Location {
+            file: file.to_string(),
+            line,
+            column,
+            length: 1,
+            source_line: content.lines().nth(line.saturating_sub(1)).unwrap_or_default().to_string(),
+        },
+        message,
+    )
+}
+
+/// Converte um valor YAML. `null` não existe no TOML, então chaves e itens nulos
+/// são descartados.
+fn from_yaml(value: serde_yaml_ng::Value) -> Option<Value> {
+    match value {
+        serde_yaml_ng::Value::Null => None,
+        serde_yaml_ng::Value::Bool(b) => Some(Value::Boolean(b)),
+        serde_yaml_ng::Value::Number(n) => match n.as_i64() {
+            Some(i) => Some(Value::Integer(i)),
+            None => n.as_f64().map(Value::Float),
+        },
+        serde_yaml_ng::Value::String(s) => Some(Value::String(s)),
+        serde_yaml_ng::Value::Sequence(items) => {
+            Some(Value::Array(items.into_iter().filter_map(from_yaml).collect()))
+        }
+        serde_yaml_ng::Value::Mapping(mapping) => {
+            let mut table = toml::Table::new();
+            for (key, item) in mapping {
+                let key = match key {
+                    serde_yaml_ng::Value::String(s) => s,
+                    other => serde_yaml_ng::to_string(&other).unwrap_or_default().trim().to_string(),
+                };
+                if let Some(item) = from_yaml(item) {
+                    table.insert(key, item);
+                }
+            }
+            Some(Value::Table(table))
+        }
+        // Tags (`!tag valor`) são ignoradas, mantendo apenas o valor
+        serde_yaml_ng::Value::Tagged(tagged) => from_yaml(tagged.value),
+    }
+}
+
+/// Converte um valor JSON, descartando `null` como em `from_yaml`
+fn from_json(value: serde_json::Value) -> Option<Value> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::Bool(b) => Some(Value::Boolean(b)),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Some(Value::Integer(i)),
+            None => n.as_f64().map(Value::Float),
+        },
+        serde_json::Value::String(s) => Some(Value::String(s)),
+        serde_json::Value::Array(items) => {
+            Some(Value::Array(items.into_iter().filter_map(from_json).collect()))
+        }
+        serde_json::Value::Object(object) => Some(Value::Table(
+            object
+                .into_iter()
+                .filter_map(|(key, item)| Some((key, from_json(item)?)))
+                .collect(),
+        )),
+    }
+}
@@ -0,0 +1,99 @@
+use toml::Value;
+
+/// Variável obrigatória (`${VAR:?mensagem}`) que não está definida
+#[derive(Debug)]
+pub(crate) struct UnsetVariable {
+    /// Caminho do valor dentro do parâmetro (ex: `env.TOKEN`, `args[1]`)
+    pub path: String,
+    pub variable: String,
+    pub message: String,
+}
+
+/// Expande variáveis de ambiente em todas as strings de `value`, inclusive
+/// dentro de arrays e tabelas.
+///
+/// Formatos suportados:
+///   `${VAR}`            -> valor de VAR ou vazio
+///   `${VAR:-default}`   -> valor de VAR ou `default` se não definida/vazia
+///   `${VAR:?mensagem}`  -> valor de VAR ou erro se não definida/vazia
+///   `$${VAR}`           -> o texto literal `${VAR}`
+pub(crate) fn expand_value(
+    value: &Value,
+    path: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<Value, UnsetVariable> {
+    match value {
+        Value::String(s) => expand_str(s, path, lookup).map(Value::String),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| expand_value(item, &format!("{}[{}]", path, i), lookup))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        Value::Table(table) => {
+            let mut expanded = toml::Table::new();
+            for (key, item) in table {
+                expanded.insert(
+                    key.clone(),
+                    expand_value(item, &format!("{}.{}", path, key), lookup)?,
+                );
+            }
+            Ok(Value::Table(expanded))
+        }
+        other => Ok(other.clone()),
+    }
+}
+
+fn expand_str(
+    input: &str,
+    path: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, UnsetVariable> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("${") {
+        // `$${` é o escape para um `${` literal
+        if rest[..start].ends_with('$') {
+            output.push_str(&rest[..start - 1]);
+            output.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+
+        let Some(end) = rest[start..].find('}') else { break };
+        output.push_str(&rest[..start]);
+
+        let expression = &rest[start + 2..start + end];
+        output.push_str(&expand_expression(expression, path, lookup)?);
+
+        rest = &rest[start + end + 1..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn expand_expression(
+    expression: &str,
+    path: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, UnsetVariable> {
+    if let Some((variable, default)) = expression.split_once(":-") {
+        return Ok(lookup(variable)
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| default.to_string()));
+    }
+
+    if let Some((variable, message)) = expression.split_once(":?") {
+        return lookup(variable)
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| UnsetVariable {
+                path: path.to_string(),
+                variable: variable.to_string(),
+                message: message.to_string(),
+            });
+    }
+
+    Ok(lookup(expression).unwrap_or_default())
+}
@@ -0,0 +1,227 @@
+use super::{ConfigError, Location, formats::Format, location::SourceSpans};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use toml::Value;
+
+/// Chave com os arquivos carregados antes do arquivo que a declara
+const INCLUDE_KEY: &str = "include";
+
+/// Tabela reservada com as seções de cada perfil (`[profile.ci.build]`)
+const PROFILE_TABLE: &str = "profile";
+
+/// Elemento de array substituído pelos elementos definidos nas camadas anteriores
+/// (ex: `features = ["...", "extra"]` acrescenta `extra` ao array herdado)
+const APPEND_MARKER: &str = "...";
+
+/// Documento de configuração formado pela fusão de um ou mais arquivos
+#[derive(Debug, Default)]
+pub(crate) struct Document {
+    pub table: toml::Table,
+    /// Posição de cada chave no último arquivo que a definiu
+    origins: HashMap<Vec<String>, Location>,
+}
+
+impl Document {
+    /// Documento de um único arquivo, sem processar `include`
+    #[cfg(test)]
+    pub fn parse(content: &str, file: &str) -> Result<Self, ConfigError> {
+        let format = Format::from_path(Path::new(file))?;
+        let mut document = Self::default();
+        document.merge(format.parse(content, file)?, &spans(format, file, content));
+        Ok(document)
+    }
+
+    /// Carrega `path` com os arquivos que ele inclui e, por cima de tudo, o arquivo
+    /// local ao lado dele (`tasks.local.toml` para `tasks.toml`), se existir.
+    ///
+    /// Tabelas são mescladas chave a chave; escalares e arrays da camada mais
+    /// recente substituem os anteriores, exceto arrays com o marcador `"..."`.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let mut document = Self::default();
+        document.load_file(path, &mut Vec::new())?;
+
+        if let Some(local) = local_path(path).filter(|local| local.is_file()) {
+            document.load_file(&local, &mut Vec::new())?;
+        }
+
+        Ok(document)
+    }
+
+    /// Aplica as seções do perfil `name` (`[profile.<name>.<task>]`) sobre o documento,
+    /// com as mesmas regras de fusão usadas entre arquivos
+    pub fn apply_profile(&mut self, name: &str) -> Result<(), ConfigError> {
+        let profiles = self.table.get(PROFILE_TABLE).and_then(|value| value.as_table());
+        let Some(profile) = profiles.and_then(|profiles| profiles.get(name)).and_then(|value| value.as_table()) else {
+            let available = profiles.map(|profiles| profiles.keys().cloned().collect()).unwrap_or_default();
+            return Err(ConfigError::ProfileNotFound(name.to_string(), available));
+        };
+        let profile = profile.clone();
+
+        // As chaves do perfil passam a apontar para a sua posição dentro de `[profile.<name>]`
+        let prefix = [PROFILE_TABLE, name];
+        let profile_origins: HashMap<Vec<String>, Location> = self
+            .origins
+            .iter()
+            .filter(|(path, _)| path.len() > prefix.len() && path[..prefix.len()] == prefix)
+            .map(|(path, location)| (path[prefix.len()..].to_vec(), location.clone()))
+            .collect();
+
+        merge_table(
+            &mut self.table,
+            profile,
+            &mut Vec::new(),
+            &|path| profile_origins.get(path).cloned(),
+            &mut self.origins,
+        );
+        Ok(())
+    }
+
+    /// Remove as seções de perfis, que não fazem parte da configuração efetiva
+    pub fn remove_profiles(&mut self) {
+        self.table.remove(PROFILE_TABLE);
+    }
+
+    /// Posição da chave indicada por `path` no arquivo de onde veio o seu valor
+    pub fn location(&self, path: &[&str]) -> Option<Location> {
+        let key: Vec<String> = path.iter().map(|s| s.to_string()).collect();
+        self.origins.get(&key).cloned()
+    }
+
+    fn load_file(&mut self, path: &Path, stack: &mut Vec<(PathBuf, String)>) -> Result<(), ConfigError> {
+        let file = path.display().to_string();
+        // Um arquivo incluído que não existe é informado junto com o arquivo que o incluiu
+        let not_found = |file: &String| match stack.last() {
+            Some((_, includer)) => ConfigError::IncludeNotFound(file.clone(), includer.clone()),
+            None => ConfigError::ConfigNotFoundError(file.clone()),
+        };
+        let canonical = fs::canonicalize(path).map_err(|_| not_found(&file))?;
+
+        if let Some(start) = stack.iter().position(|(visited, _)| *visited == canonical) {
+            let mut cycle: Vec<String> = stack[start..].iter().map(|(_, name)| name.clone()).collect();
+            cycle.push(file);
+            return Err(ConfigError::IncludeCycle(cycle));
+        }
+
+        let format = Format::from_path(path)?;
+        let content = fs::read_to_string(&canonical)
+            .map_err(|_| not_found(&file))?;
+        let mut table = format.parse(&content, &file)?;
+
+        // Os arquivos incluídos formam a base sobre a qual este arquivo é aplicado
+        let includes = match table.remove(INCLUDE_KEY) {
+            Some(value) => parse_includes(&file, &value)?,
+            None => Vec::new(),
+        };
+
+        stack.push((canonical, file.clone()));
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        for include in includes {
+            self.load_file(&base_dir.join(include), stack)?;
+        }
+        stack.pop();
+
+        self.merge(table, &spans(format, &file, &content));
+        Ok(())
+    }
+
+    fn merge(&mut self, overlay: toml::Table, spans: &SourceSpans) {
+        let locate = |path: &[String]| {
+            let path: Vec<&str> = path.iter().map(|s| s.as_str()).collect();
+            spans.key_location(&path)
+        };
+        merge_table(&mut self.table, overlay, &mut Vec::new(), &locate, &mut self.origins);
+    }
+}
+
+/// Posições das chaves só são conhecidas em arquivos TOML; nos demais formatos os
+/// valores ficam sem posição
+fn spans<'a>(format: Format, file: &'a str, content: &'a str) -> SourceSpans<'a> {
+    match format {
+        Format::Toml => SourceSpans::new(file, content),
+        Format::Yaml | Format::Json => SourceSpans::new(file, ""),
+    }
+}
+
+/// Lê a lista de arquivos incluídos (`include = ["ci/common.toml"]`)
+fn parse_includes(file: &str, value: &Value) -> Result<Vec<String>, ConfigError> {
+    let invalid = || ConfigError::InvalidInclude(file.to_string());
+
+    value
+        .as_array()
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|item| item.as_str().map(|s| s.to_string()).ok_or_else(invalid))
+        .collect()
+}
+
+/// Caminho do arquivo local de um arquivo de configuração (`tasks.toml` -> `tasks.local.toml`)
+fn local_path(path: &Path) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_str()?;
+    let name = match path.extension().and_then(|ext| ext.to_str()) {
+        Some(extension) => format!("{}.local.{}", stem, extension),
+        None => format!("{}.local", stem),
+    };
+
+    Some(path.with_file_name(name))
+}
+
+/// Aplica `overlay` sobre `base`, registrando em `origins` a posição de cada chave
+/// aplicada, obtida através de `locate`
+fn merge_table(
+    base: &mut toml::Table,
+    overlay: toml::Table,
+    path: &mut Vec<String>,
+    locate: &dyn Fn(&[String]) -> Option<Location>,
+    origins: &mut HashMap<Vec<String>, Location>,
+) {
+    for (key, value) in overlay {
+        path.push(key.clone());
+
+        if let Some(location) = locate(path) {
+            origins.insert(path.clone(), location);
+        }
+
+        match value {
+            // Tabelas são mescladas recursivamente para que cada chave tenha a sua origem
+            Value::Table(table) => {
+                let entry = base.entry(key).or_insert_with(|| Value::Table(toml::Table::new()));
+                if !entry.is_table() {
+                    *entry = Value::Table(toml::Table::new());
+                }
+                if let Value::Table(base_table) = entry {
+                    merge_table(base_table, table, path, locate, origins);
+                }
+            }
+            Value::Array(items) if items.iter().any(is_append_marker) => {
+                let inherited = match base.get(&key) {
+                    Some(Value::Array(inherited)) => inherited.clone(),
+                    _ => Vec::new(),
+                };
+
+                let merged = items
+                    .into_iter()
+                    .flat_map(|item| {
+                        if is_append_marker(&item) {
+                            inherited.clone()
+                        } else {
+                            vec![item]
+                        }
+                    })
+                    .collect();
+                base.insert(key, Value::Array(merged));
+            }
+            value => {
+                base.insert(key, value);
+            }
+        }
+
+        path.pop();
+    }
+}
+
+fn is_append_marker(value: &Value) -> bool {
+    value.as_str() == Some(APPEND_MARKER)
+}
@@ -0,0 +1,120 @@
+use core::fmt;
+use std::ops::Range;
+use serde::{Deserialize, Serialize};
+use toml::{
+    Spanned,
+    de::{DeTable, DeValue},
+};
+
+/// Posição de uma chave no arquivo de configuração
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Location {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    /// Quantidade de caracteres destacados a partir de `column`
+    pub length: usize,
+    /// Conteúdo da linha, para exibir o trecho do arquivo junto com os erros
+    pub source_line: String,
+}
+
+impl Location {
+    /// Trecho do arquivo com a posição destacada:
+    ///
+    /// ```text
+    ///   |
+    /// 3 | releese = true
+    ///   | ^^^^^^^^^^^^^^
+    /// ```
+    pub fn snippet(&self) -> Option<String> {
+        if self.source_line.trim().is_empty() {
+            return None;
+        }
+
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        // Tabs são mantidos para que os marcadores fiquem alinhados com o texto
+        let padding: String = self
+            .source_line
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        Some(format!(
+            "{gutter} |\n{number} | {}\n{gutter} | {padding}{}",
+            self.source_line,
+            "^".repeat(self.length.max(1))
+        ))
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// Localiza chaves de um documento TOML a partir do seu caminho (ex: `["build", "release"]`)
+pub(crate) struct SourceSpans<'a> {
+    file: &'a str,
+    content: &'a str,
+    root: Option<Spanned<DeTable<'a>>>,
+}
+
+impl<'a> SourceSpans<'a> {
+    pub fn new(file: &'a str, content: &'a str) -> Self {
+        Self {
+            file,
+            content,
+            root: DeTable::parse(content).ok(),
+        }
+    }
+
+    /// Posição da chave indicada por `path`. Para valores simples o destaque cobre
+    /// a chave e o valor (`release = "sim"`); para tabelas, apenas o nome.
+    pub fn key_location(&self, path: &[&str]) -> Option<Location> {
+        let mut table = self.root.as_ref()?.get_ref();
+        let (last, parents) = path.split_last()?;
+
+        for key in parents {
+            match Self::entry(table, key)?.1.get_ref() {
+                DeValue::Table(inner) => table = inner,
+                _ => return None,
+            }
+        }
+
+        let (key, value) = Self::entry(table, last)?;
+        let span = match value.get_ref() {
+            DeValue::Table(_) => key.span(),
+            _ => key.span().start..value.span().end,
+        };
+        Some(span_location(self.file, self.content, span))
+    }
+
+    fn entry<'t>(
+        table: &'t DeTable<'a>,
+        key: &str,
+    ) -> Option<(&'t Spanned<std::borrow::Cow<'a, str>>, &'t Spanned<DeValue<'a>>)> {
+        table.iter().find(|(k, _)| k.get_ref() == key)
+    }
+
+}
+
+/// Converte um trecho em bytes de `content` para linha/coluna (a partir de 1). O
+/// destaque termina no fim da linha em que o trecho começa.
+pub(crate) fn span_location(file: &str, content: &str, span: Range<usize>) -> Location {
+    let start = span.start.min(content.len());
+    let before = &content[..start];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = content[start..].find('\n').map(|i| start + i).unwrap_or(content.len());
+    let end = span.end.clamp(start, line_end);
+
+    Location {
+        file: file.to_string(),
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+        length: content[start..end].chars().count(),
+        source_line: content[line_start..line_end].trim_end_matches('\r').to_string(),
+    }
+}
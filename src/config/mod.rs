@@ -1,61 +1,442 @@
+mod discovery;
+mod duration;
 mod errors;
+mod formats;
+mod interpolation;
+mod layers;
+mod location;
+mod retry;
+mod templates;
 
 pub use errors::ConfigError;
+pub use location::Location;
+pub use duration::parse_duration_str;
+pub use retry::RetryPolicy;
+
+use crate::params::ParamType;
+use discovery::CONFIG_ENV_VAR;
+use layers::Document;
 
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fs, path::Path};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use toml::Value;
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Tabela reservada para variáveis de configuração
+const VARS_TABLE: &str = "vars";
+
+#[derive(Debug, Deserialize, Serialize, Default)]
 pub struct Config {
     pub tasks: HashMap<String, TaskConfig>,
+    /// Variáveis da tabela reservada `[vars]`, referenciadas como `{{ vars.nome }}`
+    pub vars: toml::Table,
+    /// Perfil aplicado sobre a configuração (`--profile`)
+    pub profile: Option<String>,
+    #[serde(skip)]
+    unresolved: Unresolved,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+/// Variáveis e parâmetros antes da resolução das referências, usados para resolvê-las
+/// novamente quando um parâmetro é sobrescrito
+#[derive(Debug, Default)]
+struct Unresolved {
+    vars: toml::Table,
+    /// Posição de cada variável, para indicar a origem de referências inválidas
+    var_locations: HashMap<String, Location>,
+    params: HashMap<String, HashMap<String, Value>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct TaskConfig {
+    /// Nome da task registrada que implementa esta configuração
+    pub task_type: String,
     pub enabled: bool,
+    pub depends_on: Vec<String>,
+    /// O que fazer quando a task falha (`on_failure`)
+    pub on_failure: FailurePolicy,
+    /// Novas tentativas quando a task falha (`retries`, `retry_delay`, ...)
+    pub retry: RetryPolicy,
+    /// Tempo máximo de cada tentativa de execução (`timeout`). É aplicado aos processos
+    /// iniciados pela task: quando ele se esgota, o processo em execução é encerrado e
+    /// os seguintes falham imediatamente.
+    pub timeout: Option<Duration>,
     pub params: HashMap<String, toml::Value>,
+    /// Posição da tabela da task no arquivo de configuração
+    pub location: Option<Location>,
+    /// Posição de cada parâmetro no arquivo de configuração
+    pub param_locations: HashMap<String, Location>,
+}
+
+/// Comportamento da execução quando uma task falha
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum FailurePolicy {
+    /// Não inicia mais nenhuma task (padrão)
+    #[default]
+    Stop,
+    /// Continua executando as tasks que não dependem da que falhou
+    Continue,
+    /// Trata a falha como sucesso: as dependentes executam e o código de saída não é afetado
+    Ignore,
 }
 
 impl Config {
-    pub fn from_file(path: &str) -> Result<Config, ConfigError> {
-        // Resolve o caminho
-        let absolute_path = fs::canonicalize(Path::new(path))
-            .map_err(|_| ConfigError::ConfigNotFoundError(path.to_string()))?;
+    /// Caminho do arquivo de configuração a ser usado: o informado em `--config`, o da
+    /// variável `RUNNER_CONFIG` ou o `tasks.toml` mais próximo a partir de `start`
+    pub fn locate(explicit: Option<&str>, start: &Path) -> Result<PathBuf, ConfigError> {
+        if let Some(path) = explicit {
+            return Ok(PathBuf::from(path));
+        }
+
+        if let Some(path) = std::env::var_os(CONFIG_ENV_VAR).filter(|path| !path.is_empty()) {
+            return Ok(PathBuf::from(path));
+        }
+
+        discovery::find_config(start)
+            .ok_or_else(|| ConfigError::ConfigNotDiscovered(start.display().to_string()))
+    }
+
+    /// Carrega o arquivo de configuração junto com os arquivos de `include` e o
+    /// arquivo local (`tasks.local.toml`) aplicado por cima dele. Com `profile`, as
+    /// seções `[profile.<nome>.<task>]` do perfil sobrescrevem os valores carregados.
+    pub fn from_file(path: &str, profile: Option<&str>) -> Result<Config, ConfigError> {
+        let mut document = Document::load(Path::new(path))?;
+        if let Some(profile) = profile {
+            document.apply_profile(profile)?;
+        }
+
+        let mut config = Self::from_document(document, &|name| std::env::var(name).ok())?;
+        config.profile = profile.map(|profile| profile.to_string());
+        Ok(config)
+    }
+
+    /// Interpreta o conteúdo de um único arquivo de configuração (sem `include`),
+    /// expandindo variáveis de ambiente nos parâmetros. `file` é usado apenas para
+    /// indicar a origem dos valores nas mensagens de erro.
+    #[cfg(test)]
+    pub fn parse(content: &str, file: &str) -> Result<Config, ConfigError> {
+        Self::parse_with_env(content, file, &|name| std::env::var(name).ok())
+    }
 
-        let content = std::fs::read_to_string(&absolute_path).map_err(|_| {
-            ConfigError::ConfigNotFoundError(absolute_path.to_str().unwrap_or(path).to_string())
-        })?;
+    /// Como `parse`, obtendo as variáveis de ambiente através de `env`
+    #[cfg(test)]
+    fn parse_with_env(
+        content: &str,
+        file: &str,
+        env: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Config, ConfigError> {
+        Self::from_document(Document::parse(content, file)?, env)
+    }
 
-        let config_file: Value =
-            toml::from_str(&content).map_err(|err| ConfigError::ParsingError(err.to_string()))?;
+    /// Monta a configuração a partir do documento já mesclado
+    fn from_document(
+        mut document: Document,
+        env: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Config, ConfigError> {
+        document.remove_profiles();
 
         let mut tasks = HashMap::new();
+        let mut vars = toml::Table::new();
+        let mut var_locations = HashMap::new();
+
+        // Mapeia as tabelas do arquivo de configuração para tasks e parametros.
+        // Uma tabela pode ser:
+        //   [build]                       -> task "build" do tipo "build"
+        //   [release-build] type = "build" -> task "release-build" do tipo "build"
+        //   [build.debug] / [build.release] -> tasks "build.debug" e "build.release" do tipo "build"
+        for (name, value) in &document.table {
+            let Some(task_table) = value.as_table() else { continue };
+
+            // `[vars]` é reservada para variáveis e não é uma task
+            if name == VARS_TABLE {
+                for (key, var) in task_table {
+                    let location = document.location(&[name, key]);
+                    let expanded = interpolation::expand_value(var, key, env).map_err(|unset| {
+                        let location = location.clone().map(Box::new);
+                        ConfigError::EnvVarNotSet(name.clone(), unset.path, unset.variable, unset.message, location)
+                    })?;
+                    vars.insert(key.clone(), expanded);
+                    if let Some(location) = location {
+                        var_locations.insert(key.clone(), location);
+                    }
+                }
+                continue;
+            }
+
+            if Self::is_instance_group(name, task_table) {
+                for (instance, instance_value) in task_table {
+                    let instance_name = format!("{}.{}", name, instance);
+                    let instance_table = instance_value.as_table().cloned().unwrap_or_default();
+                    let locate = |key: &str| document.location(&[name, instance, key]).map(Box::new);
+                    let mut task = Self::parse_task(&instance_name, name, &instance_table, env, &locate)?;
+                    Self::locate_task(&mut task, &document, &[name, instance]);
+                    tasks.insert(instance_name, task);
+                }
+            } else {
+                let locate = |key: &str| document.location(&[name, key]).map(Box::new);
+                let mut task = Self::parse_task(name, name, task_table, env, &locate)?;
+                Self::locate_task(&mut task, &document, &[name]);
+                tasks.insert(name.clone(), task);
+            }
+        }
+
+        // Referências entre valores só podem ser resolvidas com todas as tasks carregadas
+        let unresolved = Unresolved {
+            vars,
+            var_locations,
+            params: tasks.iter().map(|(name, task)| (name.clone(), task.params.clone())).collect(),
+        };
+        let mut config = Config {
+            tasks,
+            unresolved,
+            ..Default::default()
+        };
+        config.resolve_references()?;
+
+        Ok(config)
+    }
+
+    /// Sobrescreve um parâmetro de uma task (`--param`/`--set`) e resolve novamente as
+    /// referências, para que o valor informado possa usar `{{ ... }}` e chegue às
+    /// tasks que referenciam o parâmetro
+    pub fn set_param(&mut self, task_name: &str, key: &str, value: Value) -> Result<(), ConfigError> {
+        // O valor não vem mais do arquivo, então a posição deixa de valer
+        if let Some(task) = self.tasks.get_mut(task_name) {
+            task.param_locations.remove(key);
+        }
+
+        self.unresolved
+            .params
+            .entry(task_name.to_string())
+            .or_default()
+            .insert(key.to_string(), value);
+        self.resolve_references()
+    }
+
+    /// Recalcula `vars` e os parâmetros das tasks a partir dos valores não resolvidos
+    fn resolve_references(&mut self) -> Result<(), ConfigError> {
+        self.vars = self.unresolved.vars.clone();
+        for (name, task) in self.tasks.iter_mut() {
+            if let Some(params) = self.unresolved.params.get(name) {
+                task.params = params.clone();
+            }
+        }
+
+        templates::resolve_references(&mut self.vars, &self.unresolved.var_locations, &mut self.tasks)
+    }
+
+    /// Se a tabela `[name]` agrupa instâncias de uma task (`[build.debug]`, `[build.release]`).
+    ///
+    /// Só é um grupo quando `name` é o tipo de uma task registrada, a tabela não define
+    /// `type` e todas as sub-tabelas não são parâmetros do tipo Table dessa task; assim
+    /// `[exec] env = { ... }` continua sendo a task `exec`.
+    fn is_instance_group(name: &str, table: &toml::Table) -> bool {
+        if table.is_empty() || table.contains_key("type") || !table.values().all(|v| v.is_table()) {
+            return false;
+        }
+
+        let Some(descriptor) = crate::tasks::available_tasks().into_iter().find(|task| task.name == name) else {
+            return false;
+        };
+        let definitions = (descriptor.factory)().param_definitions();
+
+        !table.keys().any(|key| {
+            definitions
+                .iter()
+                .any(|definition| definition.name == *key && matches!(definition.param_type, ParamType::Table))
+        })
+    }
 
-        // Mapeia as tabelas do arquivo de configuração para tasks e parametros
-        if let Some(table) = config_file.as_table() {
-            for (task, params) in table {
-                if let Some(params_table) = params.as_table() {
-                    let params_map: HashMap<String, Value> = params_table
-                        .iter()
-                        .map(|(k, v)| (k.clone(), v.clone()))
-                        .collect();
-
-                    tasks.insert(
-                        task.clone(),
-                        TaskConfig {
-                            enabled: true,
-                            params: params_map,
-                        },
-                    );
+    /// Converte a tabela de uma task em `TaskConfig`, separando as chaves da task dos parâmetros.
+    /// `locate` informa a posição de cada chave da tabela, usada nas mensagens de erro.
+    fn parse_task(
+        name: &str,
+        default_type: &str,
+        table: &toml::Table,
+        env: &dyn Fn(&str) -> Option<String>,
+        locate: &dyn Fn(&str) -> Option<Box<Location>>,
+    ) -> Result<TaskConfig, ConfigError> {
+        let mut params: HashMap<String, Value> = HashMap::new();
+        for (key, value) in table {
+            let expanded = interpolation::expand_value(value, key, env).map_err(|unset| {
+                ConfigError::EnvVarNotSet(name.to_string(), unset.path, unset.variable, unset.message, locate(key))
+            })?;
+            params.insert(key.clone(), expanded);
+        }
+
+        // `type`, `enabled`, `depends_on`, `on_failure`, `retries`/`retry_*` e `timeout` são
+        // chaves da task, não parâmetros
+        let task_type = match params.remove("type") {
+            Some(value) => value.as_str().map(|s| s.to_string()).ok_or_else(|| {
+                ConfigError::InvalidTaskField(
+                    name.to_string(),
+                    "type".to_string(),
+                    "o nome de uma task registrada".to_string(),
+                    locate("type"),
+                )
+            })?,
+            None => default_type.to_string(),
+        };
+
+        let enabled = match params.remove("enabled") {
+            Some(value) => value.as_bool().ok_or_else(|| {
+                ConfigError::InvalidTaskField(
+                    name.to_string(),
+                    "enabled".to_string(),
+                    "true ou false".to_string(),
+                    locate("enabled"),
+                )
+            })?,
+            None => true,
+        };
+
+        let depends_on = match params.remove("depends_on") {
+            Some(value) => Self::parse_depends_on(name, &value, locate("depends_on"))?,
+            None => Vec::new(),
+        };
+
+        let on_failure = match params.remove("on_failure") {
+            Some(value) => match value.as_str() {
+                Some("stop") => FailurePolicy::Stop,
+                Some("continue") => FailurePolicy::Continue,
+                Some("ignore") => FailurePolicy::Ignore,
+                _ => {
+                    return Err(ConfigError::InvalidTaskField(
+                        name.to_string(),
+                        "on_failure".to_string(),
+                        "\"stop\", \"continue\" ou \"ignore\"".to_string(),
+                        locate("on_failure"),
+                    ));
                 }
+            },
+            None => FailurePolicy::default(),
+        };
+
+        let retry = Self::parse_retry(name, &mut params, locate)?;
+
+        let timeout = match params.remove("timeout") {
+            Some(value) => {
+                let timeout = duration::parse_duration(&value).filter(|timeout| !timeout.is_zero());
+                Some(timeout.ok_or_else(|| {
+                    ConfigError::InvalidTaskField(
+                        name.to_string(),
+                        "timeout".to_string(),
+                        "uma duração maior que zero, como \"30s\" ou \"5m\"".to_string(),
+                        locate("timeout"),
+                    )
+                })?)
+            }
+            None => None,
+        };
+
+        Ok(TaskConfig {
+            task_type,
+            enabled,
+            depends_on,
+            on_failure,
+            retry,
+            timeout,
+            params,
+            ..Default::default()
+        })
+    }
+
+    /// Lê as chaves de novas tentativas: `retries`, `retry_delay`, `retry_backoff` e `retry_jitter`
+    fn parse_retry(
+        task: &str,
+        params: &mut HashMap<String, Value>,
+        locate: &dyn Fn(&str) -> Option<Box<Location>>,
+    ) -> Result<RetryPolicy, ConfigError> {
+        let invalid = |field: &str, expected: &str| {
+            ConfigError::InvalidTaskField(task.to_string(), field.to_string(), expected.to_string(), locate(field))
+        };
+        let mut flag = |field: &str| match params.remove(field) {
+            Some(value) => value.as_bool().ok_or_else(|| invalid(field, "true ou false")),
+            None => Ok(false),
+        };
+        let backoff = flag("retry_backoff")?;
+        let jitter = flag("retry_jitter")?;
+
+        let retries = match params.remove("retries") {
+            Some(value) => value
+                .as_integer()
+                .and_then(|retries| u32::try_from(retries).ok())
+                .ok_or_else(|| invalid("retries", "um número inteiro maior ou igual a zero"))?,
+            None => 0,
+        };
+
+        let delay = match params.remove("retry_delay") {
+            Some(value) => duration::parse_duration(&value)
+                .ok_or_else(|| invalid("retry_delay", "uma duração como \"500ms\", \"2s\" ou \"1m\""))?,
+            None => retry::DEFAULT_RETRY_DELAY,
+        };
+
+        Ok(RetryPolicy {
+            retries,
+            delay,
+            backoff,
+            jitter,
+        })
+    }
+
+    /// Preenche as posições da tabela da task (em `path`) e de cada parâmetro
+    fn locate_task(task: &mut TaskConfig, document: &Document, path: &[&str]) {
+        task.location = document.location(path);
+
+        for param in task.params.keys() {
+            let mut param_path = path.to_vec();
+            param_path.push(param);
+
+            if let Some(location) = document.location(&param_path) {
+                task.param_locations.insert(param.clone(), location);
             }
         }
+    }
+
+    /// Lê a lista de dependências de uma task (`depends_on = ["build", "test"]`)
+    fn parse_depends_on(task: &str, value: &Value, location: Option<Box<Location>>) -> Result<Vec<String>, ConfigError> {
+        let invalid = || {
+            ConfigError::InvalidTaskField(
+                task.to_string(),
+                "depends_on".to_string(),
+                "um array de nomes de tasks".to_string(),
+                location.clone(),
+            )
+        };
+
+        value
+            .as_array()
+            .ok_or_else(invalid)?
+            .iter()
+            .map(|dep| dep.as_str().map(|s| s.to_string()).ok_or_else(invalid))
+            .collect()
+    }
+
+    /// Interpreta uma atribuição `chave=valor` vinda da linha de comando.
+    ///
+    /// O valor é lido como um literal TOML (`true`, `3`, `["a", "b"]`, `"texto"`) para
+    /// que tenha o tipo correto; se não for um literal válido, é usado como string.
+    pub fn parse_assignment(assignment: &str) -> Result<(String, Value), ConfigError> {
+        let (key, raw_value) = assignment
+            .split_once('=')
+            .filter(|(key, _)| !key.trim().is_empty())
+            .ok_or_else(|| ConfigError::InvalidOverride(assignment.to_string()))?;
 
-        return Ok(Config { tasks });
+        let raw_value = raw_value.trim();
+        let value = raw_value
+            .parse::<Value>()
+            .unwrap_or_else(|_| Value::String(raw_value.to_string()));
+
+        Ok((key.trim().to_string(), value))
     }
 
-    pub fn find_task(&self, task_name: &str) -> &TaskConfig {
-        self.tasks.get(task_name).unwrap()
+    pub fn find_task(&self, task_name: &str) -> Option<&TaskConfig> {
+        self.tasks.get(task_name)
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+/// Intervalo entre tentativas quando `retries` é informado sem `retry_delay`
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Como uma task que falhou é executada novamente
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+pub struct RetryPolicy {
+    /// Quantas novas tentativas são feitas depois da primeira falha (`retries`)
+    pub retries: u32,
+    /// Intervalo antes da primeira nova tentativa (`retry_delay`)
+    pub delay: Duration,
+    /// Dobra o intervalo a cada nova tentativa (`retry_backoff`)
+    pub backoff: bool,
+    /// Sorteia o intervalo entre a metade e o valor calculado, para que tasks que
+    /// falharam juntas não tentem de novo ao mesmo tempo (`retry_jitter`)
+    pub jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Intervalo de espera antes da nova tentativa número `retry` (a partir de 1)
+    pub fn delay_before(&self, retry: u32) -> Duration {
+        let mut delay = self.delay;
+
+        if self.backoff {
+            let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+            delay = delay.saturating_mul(factor);
+        }
+
+        if self.jitter {
+            delay = delay.mul_f64(0.5 + 0.5 * random_fraction());
+        }
+
+        delay
+    }
+}
+
+/// Número pseudoaleatório em `[0, 1]`, suficiente para espalhar as tentativas
+fn random_fraction() -> f64 {
+    let hash = RandomState::new().build_hasher().finish();
+    hash as f64 / u64::MAX as f64
+}
@@ -0,0 +1,165 @@
+use super::{ConfigError, Location, TaskConfig};
+use std::collections::HashMap;
+use toml::Value;
+
+/// Resolve referências `{{ vars.nome }}` e `{{ tasks.<task>.<parametro> }}` nas
+/// variáveis e nos parâmetros das tasks.
+///
+/// Quando a string inteira é uma única referência, o valor referenciado é usado
+/// com o seu tipo original (ex: `release = "{{ vars.release }}"` vira um Bool);
+/// caso contrário ele é convertido para texto.
+///
+/// Outros `{{ ... }}` (ex: `docker ps --format "{{.Names}}"`) são mantidos como
+/// estão, e `\{{` é o escape para um `{{` literal (`\{{ vars.x }}` -> `{{ vars.x }}`).
+///
+/// `var_locations` e as posições dos parâmetros das tasks indicam, nos erros, onde
+/// está o valor com a referência inválida.
+pub(crate) fn resolve_references(
+    vars: &mut toml::Table,
+    var_locations: &HashMap<String, Location>,
+    tasks: &mut HashMap<String, TaskConfig>,
+) -> Result<(), ConfigError> {
+    let mut sources: HashMap<String, Value> = HashMap::new();
+    let mut locations: HashMap<String, Location> = HashMap::new();
+
+    for (name, value) in vars.iter() {
+        let source = format!("vars.{}", name);
+        if let Some(location) = var_locations.get(name) {
+            locations.insert(source.clone(), location.clone());
+        }
+        sources.insert(source, value.clone());
+    }
+    for (task_name, task) in tasks.iter() {
+        for (param, value) in &task.params {
+            let source = format!("tasks.{}.{}", task_name, param);
+            if let Some(location) = task.param_locations.get(param) {
+                locations.insert(source.clone(), location.clone());
+            }
+            sources.insert(source, value.clone());
+        }
+    }
+
+    let mut resolver = Resolver {
+        sources,
+        locations,
+        resolved: HashMap::new(),
+        stack: Vec::new(),
+    };
+
+    for (name, value) in vars.iter_mut() {
+        *value = resolver.resolve(&format!("vars.{}", name))?;
+    }
+    for (task_name, task) in tasks.iter_mut() {
+        for (param, value) in task.params.iter_mut() {
+            *value = resolver.resolve(&format!("tasks.{}.{}", task_name, param))?;
+        }
+    }
+
+    Ok(())
+}
+
+struct Resolver {
+    /// Valores originais, indexados pelo nome usado nas referências
+    sources: HashMap<String, Value>,
+    /// Posições dos valores originais no arquivo de configuração
+    locations: HashMap<String, Location>,
+    resolved: HashMap<String, Value>,
+    /// Referências sendo resolvidas no momento, para detectar ciclos
+    stack: Vec<String>,
+}
+
+impl Resolver {
+    fn resolve(&mut self, name: &str) -> Result<Value, ConfigError> {
+        if let Some(value) = self.resolved.get(name) {
+            return Ok(value.clone());
+        }
+
+        if let Some(start) = self.stack.iter().position(|item| item == name) {
+            let mut cycle = self.stack[start..].to_vec();
+            cycle.push(name.to_string());
+            return Err(ConfigError::ReferenceCycle(cycle));
+        }
+
+        let Some(source) = self.sources.get(name).cloned() else {
+            let owner = self.stack.last().cloned().unwrap_or_default();
+            let location = self.locations.get(&owner).cloned().map(Box::new);
+            return Err(ConfigError::UnknownReference(owner, name.to_string(), location));
+        };
+
+        self.stack.push(name.to_string());
+        let value = self.expand(&source)?;
+        self.stack.pop();
+
+        self.resolved.insert(name.to_string(), value.clone());
+        Ok(value)
+    }
+
+    fn expand(&mut self, value: &Value) -> Result<Value, ConfigError> {
+        match value {
+            Value::String(s) => self.expand_str(s),
+            Value::Array(items) => items
+                .iter()
+                .map(|item| self.expand(item))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array),
+            Value::Table(table) => {
+                let mut expanded = toml::Table::new();
+                for (key, item) in table {
+                    expanded.insert(key.clone(), self.expand(item)?);
+                }
+                Ok(Value::Table(expanded))
+            }
+            other => Ok(other.clone()),
+        }
+    }
+
+    fn expand_str(&mut self, input: &str) -> Result<Value, ConfigError> {
+        // Uma única referência ocupando a string inteira mantém o tipo do valor
+        let trimmed = input.trim();
+        if let Some(inner) = trimmed.strip_prefix("{{").and_then(|s| s.strip_suffix("}}"))
+            && !inner.contains("{{")
+            && !inner.contains("}}")
+            && is_reference(inner.trim())
+        {
+            return self.resolve(inner.trim());
+        }
+
+        let mut output = String::with_capacity(input.len());
+        let mut rest = input;
+
+        while let Some(start) = rest.find("{{") {
+            // `\{{` é o escape para um `{{` literal
+            if rest[..start].ends_with('\\') {
+                output.push_str(&rest[..start - 1]);
+                output.push_str("{{");
+                rest = &rest[start + 2..];
+                continue;
+            }
+
+            let Some(end) = rest[start..].find("}}") else { break };
+            let reference = rest[start + 2..start + end].trim();
+
+            if !is_reference(reference) {
+                output.push_str(&rest[..start + end + 2]);
+                rest = &rest[start + end + 2..];
+                continue;
+            }
+
+            output.push_str(&rest[..start]);
+            match self.resolve(reference)? {
+                Value::String(s) => output.push_str(&s),
+                other => output.push_str(&other.to_string()),
+            }
+
+            rest = &rest[start + end + 2..];
+        }
+
+        output.push_str(rest);
+        Ok(Value::String(output))
+    }
+}
+
+/// Se o conteúdo de um `{{ ... }}` é uma referência resolvida pelo runner
+fn is_reference(reference: &str) -> bool {
+    reference.starts_with("vars.") || reference.starts_with("tasks.")
+}
@@ -0,0 +1,520 @@
+use super::*;
+use crate::test_utils::TempDir;
+use std::{fs, path::PathBuf, time::Duration};
+
+#[test]
+fn test_task_keys_are_not_params() {
+    let config = Config::parse(
+        r#"
+        [git-tag]
+        tag = "v1.0.0"
+        enabled = false
+        depends_on = ["build"]
+        "#,
+        "tasks.toml",
+    )
+    .unwrap();
+
+    let task = config.find_task("git-tag").unwrap();
+    assert_eq!(task.task_type, "git-tag");
+    assert!(!task.enabled);
+    assert_eq!(task.depends_on, vec!["build"]);
+    assert_eq!(task.params.len(), 1);
+}
+
+#[test]
+fn test_nested_instances() {
+    let config = Config::parse(
+        r#"
+        [build.debug]
+
+        [build.release]
+        release = true
+        "#,
+        "tasks.toml",
+    )
+    .unwrap();
+
+    assert_eq!(config.tasks.len(), 2);
+    assert_eq!(config.find_task("build.debug").unwrap().task_type, "build");
+    assert_eq!(config.find_task("build.release").unwrap().task_type, "build");
+    assert_eq!(
+        config.find_task("build.release").unwrap().params.get("release"),
+        Some(&Value::Boolean(true))
+    );
+}
+
+#[test]
+fn test_table_params_are_not_instances() {
+    let config = Config::parse(
+        r#"
+        [exec]
+        env = { A = "1" }
+
+        [envonly]
+        env = { A = "1" }
+        "#,
+        "tasks.toml",
+    )
+    .unwrap();
+
+    // `env` é um parâmetro Table da task `exec`, e `envonly` não é um tipo de task
+    assert_eq!(config.tasks.len(), 2);
+    assert_eq!(config.find_task("exec").unwrap().task_type, "exec");
+    assert!(config.find_task("exec").unwrap().params.contains_key("env"));
+    assert_eq!(config.find_task("envonly").unwrap().task_type, "envonly");
+    assert!(config.find_task("envonly.env").is_none());
+}
+
+#[test]
+fn test_explicit_type() {
+    let config = Config::parse(
+        r#"
+        [release-build]
+        type = "build"
+        release = true
+        "#,
+        "tasks.toml",
+    )
+    .unwrap();
+
+    let task = config.find_task("release-build").unwrap();
+    assert_eq!(task.task_type, "build");
+    assert!(!task.params.contains_key("type"));
+}
+
+#[test]
+fn test_invalid_depends_on() {
+    let result = Config::parse(
+        r#"
+        [git-tag]
+        depends_on = "build"
+        "#,
+        "tasks.toml",
+    );
+
+    assert!(matches!(result, Err(ConfigError::InvalidTaskField(..))));
+}
+
+#[test]
+fn test_param_locations() {
+    let config = Config::parse("[git-tag]\ntag = \"v1\"\n\n[build.release]\n  release = true\n", "tasks.toml").unwrap();
+
+    let git_tag = config.find_task("git-tag").unwrap();
+    assert_eq!(git_tag.location.as_ref().unwrap().to_string(), "tasks.toml:1:2");
+    assert_eq!(git_tag.param_locations["tag"].to_string(), "tasks.toml:2:1");
+
+    let release = config.find_task("build.release").unwrap();
+    assert_eq!(release.param_locations["release"].to_string(), "tasks.toml:5:3");
+}
+
+#[test]
+fn test_parse_assignment() {
+    assert_eq!(
+        Config::parse_assignment("push=true").unwrap(),
+        ("push".to_string(), Value::Boolean(true))
+    );
+    assert_eq!(
+        Config::parse_assignment("tag=v1.2.3").unwrap(),
+        ("tag".to_string(), Value::String("v1.2.3".to_string()))
+    );
+    assert_eq!(
+        Config::parse_assignment("features=[\"a\"]").unwrap().1,
+        Value::Array(vec![Value::String("a".to_string())])
+    );
+    assert!(Config::parse_assignment("sem-valor").is_err());
+}
+
+fn test_env(name: &str) -> Option<String> {
+    match name {
+        "CI_COMMIT_TAG" => Some("v2.0.0".to_string()),
+        "EMPTY" => Some(String::new()),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_env_interpolation() {
+    let config = Config::parse_with_env(
+        r#"
+        [exec]
+        command = "echo ${CI_COMMIT_TAG}"
+        args = ["${MISSING:-padrão}", "${EMPTY:-vazio}", "$${CI_COMMIT_TAG}"]
+        env = { TAG = "tag-${CI_COMMIT_TAG}" }
+        "#,
+        "tasks.toml",
+        &test_env,
+    )
+    .unwrap();
+
+    let params = &config.find_task("exec").unwrap().params;
+    assert_eq!(params["command"].as_str(), Some("echo v2.0.0"));
+    assert_eq!(
+        params["args"],
+        Value::Array(vec![
+            Value::String("padrão".to_string()),
+            Value::String("vazio".to_string()),
+            Value::String("${CI_COMMIT_TAG}".to_string()),
+        ])
+    );
+    assert_eq!(params["env"]["TAG"].as_str(), Some("tag-v2.0.0"));
+}
+
+#[test]
+fn test_required_env_var() {
+    let result = Config::parse_with_env(
+        r#"
+        [git-tag]
+        tag = "${RELEASE_TAG:?defina a versão}"
+        "#,
+        "tasks.toml",
+        &test_env,
+    );
+
+    match result {
+        Err(err @ ConfigError::EnvVarNotSet(..)) => {
+            let message = err.to_string();
+            assert!(message.contains("git-tag"));
+            assert!(message.contains("'tag'"));
+            assert!(message.contains("defina a versão"));
+            assert!(message.contains("tasks.toml:3:9"));
+        }
+        other => panic!("esperado erro de variável, obtido {:?}", other),
+    }
+}
+
+#[test]
+fn test_vars_and_task_references() {
+    let config = Config::parse(
+        r#"
+        [vars]
+        version = "1.2.3"
+        tag = "v{{ vars.version }}"
+        release = true
+
+        [git-tag]
+        tag = "{{ vars.tag }}"
+        message = "Versão {{ tasks.git-tag.tag }}"
+
+        [build]
+        release = "{{ vars.release }}"
+        "#,
+        "tasks.toml",
+    )
+    .unwrap();
+
+    assert!(!config.tasks.contains_key("vars"));
+
+    let git_tag = &config.find_task("git-tag").unwrap().params;
+    assert_eq!(git_tag["tag"].as_str(), Some("v1.2.3"));
+    assert_eq!(git_tag["message"].as_str(), Some("Versão v1.2.3"));
+    assert_eq!(config.find_task("build").unwrap().params["release"], Value::Boolean(true));
+}
+
+#[test]
+fn test_reference_cycle() {
+    let result = Config::parse(
+        r#"
+        [vars]
+        a = "{{ vars.b }}"
+        b = "{{ tasks.exec.command }}"
+
+        [exec]
+        command = "{{ vars.a }}"
+        "#,
+        "tasks.toml",
+    );
+
+    assert!(matches!(result, Err(ConfigError::ReferenceCycle(_))));
+}
+
+#[test]
+fn test_unknown_reference() {
+    let result = Config::parse(
+        r#"
+        [exec]
+        command = "{{ vars.inexistente }}"
+        "#,
+        "tasks.toml",
+    );
+
+    match result {
+        Err(ConfigError::UnknownReference(owner, _, Some(location))) => {
+            assert_eq!(owner, "tasks.exec.command");
+            assert_eq!((location.file.as_str(), location.line), ("tasks.toml", 3));
+        }
+        other => panic!("esperado erro de referência, obtido {:?}", other),
+    }
+}
+
+#[test]
+fn test_foreign_templates_are_kept() {
+    let config = Config::parse(
+        r#"
+        [exec]
+        args = ["ps", "--format", "{{.Names}}", "{{ json .State }}"]
+        "#,
+        "tasks.toml",
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.find_task("exec").unwrap().params["args"],
+        Value::Array(vec![
+            Value::from("ps"),
+            Value::from("--format"),
+            Value::from("{{.Names}}"),
+            Value::from("{{ json .State }}"),
+        ])
+    );
+}
+
+#[test]
+fn test_escaped_reference() {
+    let config = Config::parse(
+        r#"
+        [vars]
+        version = "1.0"
+
+        [exec]
+        command = '\{{ vars.version }} = {{ vars.version }}'
+        "#,
+        "tasks.toml",
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.find_task("exec").unwrap().params["command"].as_str(),
+        Some("{{ vars.version }} = 1.0")
+    );
+}
+
+#[test]
+fn test_includes_and_local_overlay() {
+    let dir = TempDir::new();
+    let common = dir.write(
+        "ci/common.toml",
+        "[build]\nrelease = false\nfeatures = [\"base\"]\n\n[git-tag]\ntag = \"v1\"\n",
+    );
+    let main = dir.write(
+        "tasks.toml",
+        "include = [\"ci/common.toml\"]\n\n[build]\nfeatures = [\"...\", \"extra\"]\n",
+    );
+    let local = dir.write("tasks.local.toml", "[build]\nrelease = true\n");
+
+    let config = Config::from_file(&main, None).unwrap();
+    let build = config.find_task("build").unwrap();
+
+    assert_eq!(build.params["release"], Value::Boolean(true));
+    assert_eq!(
+        build.params["features"],
+        Value::Array(vec![Value::from("base"), Value::from("extra")])
+    );
+    assert_eq!(config.find_task("git-tag").unwrap().params["tag"].as_str(), Some("v1"));
+
+    // Cada valor aponta para o arquivo que o definiu por último
+    assert_eq!(build.param_locations["release"].file, local);
+    assert_eq!(build.param_locations["features"].file, main);
+    assert_eq!(config.find_task("git-tag").unwrap().param_locations["tag"].file, common);
+}
+
+#[test]
+fn test_include_cycle() {
+    let dir = TempDir::new();
+    dir.write("a.toml", "include = [\"b.toml\"]\n");
+    let main = dir.write("b.toml", "include = [\"a.toml\"]\n");
+
+    let result = Config::from_file(&main, None);
+    assert!(matches!(result, Err(ConfigError::IncludeCycle(ref cycle)) if cycle.len() == 3));
+}
+
+#[test]
+fn test_errors_point_to_included_file() {
+    let dir = TempDir::new();
+    let common = dir.write("ci/common.toml", "[build]
+enabled = \"sim\"
+");
+    let main = dir.write("tasks.toml", "include = [\"ci/common.toml\"]
+");
+
+    match Config::from_file(&main, None) {
+        Err(err @ ConfigError::InvalidTaskField(..)) => {
+            let message = err.to_string();
+            assert!(message.contains(&format!("{}:2:1", common)), "{}", message);
+            assert!(message.contains("enabled = \"sim\""), "{}", message);
+        }
+        other => panic!("esperado erro de campo inválido, obtido {:?}", other),
+    }
+}
+
+#[test]
+fn test_missing_include_names_includer() {
+    let dir = TempDir::new();
+    let main = dir.write("tasks.toml", "include = [\"ci/inexistente.toml\"]
+");
+
+    let result = Config::from_file(&main, None);
+    assert!(matches!(result, Err(ConfigError::IncludeNotFound(_, ref includer)) if *includer == main));
+}
+
+#[test]
+fn test_profile_overrides() {
+    let dir = TempDir::new();
+    let main = dir.write(
+        "tasks.toml",
+        "[build]\nrelease = false\n\n[profile.ci.build]\nrelease = true\n",
+    );
+
+    let config = Config::from_file(&main, None).unwrap();
+    assert!(!config.tasks.contains_key("profile"));
+    assert_eq!(config.find_task("build").unwrap().params["release"], Value::Boolean(false));
+
+    let config = Config::from_file(&main, Some("ci")).unwrap();
+    let build = config.find_task("build").unwrap();
+    assert_eq!(build.params["release"], Value::Boolean(true));
+    assert_eq!(build.param_locations["release"].line, 5);
+
+    let result = Config::from_file(&main, Some("release"));
+    assert!(matches!(result, Err(ConfigError::ProfileNotFound(_, ref available)) if available == &["ci"]));
+}
+
+#[test]
+fn test_config_discovery() {
+    let dir = TempDir::new();
+    let config = dir.write("repo/tasks.toml", "");
+    dir.write("repo/.git/HEAD", "");
+    dir.write("repo/crates/app/src/main.rs", "");
+    dir.write("tasks.toml", "");
+
+    let nested = dir.path().join("repo/crates/app/src");
+    assert_eq!(discovery::find_config(&nested), Some(PathBuf::from(&config)));
+
+    // A busca não passa da raiz do repositório git
+    fs::remove_file(&config).unwrap();
+    assert_eq!(discovery::find_config(&nested), None);
+
+    let explicit = Config::locate(Some("outro.toml"), &nested).unwrap();
+    assert_eq!(explicit, PathBuf::from("outro.toml"));
+}
+
+#[test]
+fn test_yaml_and_json_configs() {
+    let yaml = Config::parse(
+        "build:\n  release: true\n  features: [a, b]\ngit-tag:\n  tag: v1\n  message: ~\n",
+        "tasks.yaml",
+    )
+    .unwrap();
+    let json = Config::parse(
+        r#"{"build": {"release": true, "features": ["a", "b"]}, "git-tag": {"tag": "v1", "message": null}}"#,
+        "tasks.json",
+    )
+    .unwrap();
+
+    for config in [yaml, json] {
+        let build = config.find_task("build").unwrap();
+        assert_eq!(build.params["release"], Value::Boolean(true));
+        assert_eq!(build.params["features"], Value::Array(vec![Value::from("a"), Value::from("b")]));
+        assert!(!config.find_task("git-tag").unwrap().params.contains_key("message"));
+    }
+}
+
+#[test]
+fn test_parsing_error_location() {
+    let cases = [
+        ("tasks.toml", "[build]\nrelease = = true\n", 2),
+        ("tasks.yaml", "build:\n  release: true\n  - x\n", 3),
+        ("tasks.json", "{\n  \"build\": {\n    \"release\": [1 2]\n  }\n}", 3),
+    ];
+
+    for (file, content, line) in cases {
+        match Config::parse(content, file) {
+            Err(ConfigError::ParsingError(location, _)) => {
+                assert_eq!(location.file, file);
+                assert_eq!(location.line, line, "{}", file);
+                assert!(location.column > 1, "{}", file);
+            }
+            other => panic!("{}: esperado ParsingError, recebido {:?}", file, other),
+        }
+    }
+}
+
+#[test]
+fn test_location_snippet() {
+    let config = Config::parse("[build]\nrelease = \"sim\"\n", "tasks.toml").unwrap();
+    let build = config.find_task("build").unwrap();
+
+    assert_eq!(
+        build.param_locations["release"].snippet().unwrap(),
+        "  |\n2 | release = \"sim\"\n  | ^^^^^^^^^^^^^^^"
+    );
+    assert_eq!(build.location.as_ref().unwrap().snippet().unwrap(), "  |\n1 | [build]\n  |  ^^^^^");
+}
+
+#[test]
+fn test_on_failure() {
+    let config = Config::parse("[exec]\non_failure = \"continue\"\n", "tasks.toml").unwrap();
+    let exec = config.find_task("exec").unwrap();
+    assert_eq!(exec.on_failure, FailurePolicy::Continue);
+    assert!(!exec.params.contains_key("on_failure"));
+
+    let result = Config::parse("[exec]\non_failure = \"retry\"\n", "tasks.toml");
+    assert!(matches!(result, Err(ConfigError::InvalidTaskField(..))));
+}
+
+#[test]
+fn test_retry_policy() {
+    let config = Config::parse(
+        "[exec]\nretries = 2\nretry_delay = \"500ms\"\nretry_backoff = true\n",
+        "tasks.toml",
+    )
+    .unwrap();
+    let exec = config.find_task("exec").unwrap();
+    assert_eq!(exec.retry.retries, 2);
+    assert_eq!(exec.retry.delay, Duration::from_millis(500));
+    assert_eq!(exec.retry.delay_before(3), Duration::from_secs(2));
+    assert!(!exec.params.contains_key("retries"));
+
+    let result = Config::parse("[exec]\nretries = 1\nretry_delay = \"logo\"\n", "tasks.toml");
+    assert!(matches!(result, Err(ConfigError::InvalidTaskField(..))));
+}
+
+#[test]
+fn test_timeout() {
+    let config = Config::parse("[exec]\ntimeout = \"5m\"\n\n[build]\n", "tasks.toml").unwrap();
+    assert_eq!(config.find_task("exec").unwrap().timeout, Some(Duration::from_secs(300)));
+    assert_eq!(config.find_task("build").unwrap().timeout, None);
+
+    for invalid in ["\"0s\"", "\"logo\"", "-1"] {
+        let result = Config::parse(&format!("[exec]\ntimeout = {}\n", invalid), "tasks.toml");
+        assert!(matches!(result, Err(ConfigError::InvalidTaskField(..))), "{}", invalid);
+    }
+}
+
+#[test]
+fn test_overrides_are_resolved_with_references() {
+    let mut config = Config::parse(
+        r#"
+        [vars]
+        remote = "origin"
+
+        [git-tag]
+        tag = "v1"
+
+        [exec]
+        args = ["{{ tasks.git-tag.tag }}"]
+        "#,
+        "tasks.toml",
+    )
+    .unwrap();
+
+    config.set_param("git-tag", "tag", Value::from("v2")).unwrap();
+    config.set_param("git-tag", "remote", Value::from("{{ vars.remote }}")).unwrap();
+
+    let git_tag = &config.find_task("git-tag").unwrap().params;
+    assert_eq!(git_tag["tag"].as_str(), Some("v2"));
+    assert_eq!(git_tag["remote"].as_str(), Some("origin"));
+    assert_eq!(config.find_task("exec").unwrap().params["args"], Value::Array(vec![Value::from("v2")]));
+
+    let result = config.set_param("exec", "command", Value::from("{{ vars.inexistente }}"));
+    assert!(matches!(result, Err(ConfigError::UnknownReference(..))));
+}
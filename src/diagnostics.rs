@@ -0,0 +1,66 @@
+//! Problemas de configuração reportados em conjunto, antes da execução das tasks
+
+use crate::{
+    config::{Location, TaskConfig},
+    params::{ParamError, ParamErrorKind},
+};
+use core::fmt;
+
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub task: String,
+    pub message: String,
+    pub location: Option<Location>,
+}
+
+impl Diagnostic {
+    pub fn new(task: &str, message: impl Into<String>, location: Option<Location>) -> Self {
+        Self {
+            task: task.to_string(),
+            message: message.into(),
+            location,
+        }
+    }
+
+    /// Diagnóstico de um parâmetro inválido, apontando para a chave (ou para a
+    /// tabela da task quando o parâmetro está ausente)
+    pub fn from_param_error(task: &str, config: &TaskConfig, error: &ParamError) -> Self {
+        let location = match error.kind {
+            ParamErrorKind::Missing => None,
+            _ => config.param_locations.get(&error.param).cloned(),
+        }
+        .or_else(|| config.location.clone());
+
+        Self::new(task, error.to_string(), location)
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{}: ", location)?;
+        }
+        write!(f, "[{}] {}", self.task, self.message)?;
+
+        if let Some(snippet) = self.location.as_ref().and_then(|location| location.snippet()) {
+            for line in snippet.lines() {
+                write!(f, "\n    {}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Lista de diagnósticos exibida de uma só vez
+#[derive(Debug)]
+pub struct Diagnostics(pub Vec<Diagnostic>);
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} problema(s) encontrado(s) na configuração:", self.0.len())?;
+        for diagnostic in &self.0 {
+            write!(f, "\n  • {}", diagnostic)?;
+        }
+        Ok(())
+    }
+}
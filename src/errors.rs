@@ -1,9 +1,102 @@
+//! Erros da aplicação e os códigos de saída do processo associados a eles.
+//!
+//! | Código | Situação                                                          |
+//! |--------|-------------------------------------------------------------------|
+//! | 0      | Sucesso                                                           |
+//! | 1      | Falha na execução de uma task                                     |
+//! | 2      | Uso incorreto da linha de comando                                 |
+//! | 3      | Configuração inválida (arquivo, parâmetros ou ciclo de dependência) |
+//! | 4      | Task não encontrada                                               |
+//! | 5      | Erro de entrada/saída                                             |
+//! | 6      | Task interrompida por exceder o tempo limite (`timeout`)          |
+
+use crate::{config::ConfigError, diagnostics::Diagnostics, params::ParamErrors, registry::TaskError};
 use core::fmt;
+use std::time::Duration;
+
+pub const EXIT_TASK_FAILED: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_CONFIG: i32 = 3;
+pub const EXIT_TASK_NOT_FOUND: i32 = 4;
+pub const EXIT_IO: i32 = 5;
+pub const EXIT_TIMEOUT: i32 = 6;
 
 #[derive(Debug)]
 pub enum AppError {
-    Config(crate::config::ConfigError),
+    Config(ConfigError),
     Generic(String),
+    /// Argumentos inválidos na linha de comando
+    Usage(String),
+    /// Task que não está configurada ou cujo tipo não está registrado
+    TaskNotFound {
+        task: String,
+        available: String,
+    },
+    /// Problemas de configuração encontrados antes da execução
+    Validation(Diagnostics),
+    /// Parâmetros inválidos detectados ao executar uma task
+    InvalidParams(ParamErrors),
+    DependencyCycle(Vec<String>),
+    /// Falha durante a execução de uma task
+    TaskFailed {
+        task: String,
+        source: Box<AppError>,
+    },
+    /// Mais de uma task falhou (`on_failure = "continue"` ou `--keep-going`)
+    TasksFailed(Vec<AppError>),
+    CommandFailed {
+        command: String,
+        status: Option<i32>,
+        stderr: String,
+    },
+    Io {
+        context: String,
+        source: std::io::Error,
+    },
+    /// Comando encerrado (junto com o seu grupo de processos) por exceder o `timeout`
+    Timeout {
+        command: String,
+        timeout: Duration,
+    },
+}
+
+impl AppError {
+    /// Se executar a task novamente pode dar outro resultado. Erros de configuração, de
+    /// uso e os genéricos das tasks são permanentes; falhas de comandos, de I/O e por
+    /// tempo limite podem ser temporárias.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AppError::Usage(_)
+            | AppError::Config(_)
+            | AppError::Validation(_)
+            | AppError::InvalidParams(_)
+            | AppError::DependencyCycle(_)
+            | AppError::TaskNotFound { .. }
+            | AppError::Generic(_) => false,
+            AppError::TaskFailed { source, .. } => source.is_retryable(),
+            AppError::TasksFailed(failures) => failures.iter().all(|failure| failure.is_retryable()),
+            AppError::CommandFailed { .. } | AppError::Io { .. } | AppError::Timeout { .. } => true,
+        }
+    }
+
+    /// Código de saída do processo para este erro (veja a tabela no início do módulo)
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::Usage(_) => EXIT_USAGE,
+            AppError::Config(_)
+            | AppError::Validation(_)
+            | AppError::InvalidParams(_)
+            | AppError::DependencyCycle(_) => EXIT_CONFIG,
+            AppError::TaskNotFound { .. } => EXIT_TASK_NOT_FOUND,
+            AppError::Io { .. } => EXIT_IO,
+            AppError::Timeout { .. } => EXIT_TIMEOUT,
+            AppError::TaskFailed { source, .. } if matches!(**source, AppError::Timeout { .. }) => EXIT_TIMEOUT,
+            AppError::Generic(_)
+            | AppError::TaskFailed { .. }
+            | AppError::TasksFailed(_)
+            | AppError::CommandFailed { .. } => EXIT_TASK_FAILED,
+        }
+    }
 }
 
 impl fmt::Display for AppError {
@@ -11,14 +104,75 @@ impl fmt::Display for AppError {
         match self {
             AppError::Config(err) => write!(f, "Erro de configuração: {}", err),
             AppError::Generic(msg) => write!(f, "{}", msg),
+            AppError::Usage(msg) => write!(f, "{}", msg),
+            AppError::TaskNotFound { task, available } => write!(
+                f,
+                "Task '{}' não encontrada. Tasks disponíveis: {}",
+                task, available
+            ),
+            AppError::Validation(diagnostics) => write!(f, "{}", diagnostics),
+            AppError::InvalidParams(errors) => write!(f, "{}", errors),
+            AppError::DependencyCycle(cycle) => {
+                write!(f, "Dependência circular entre tasks: {}", cycle.join(" -> "))
+            }
+            AppError::TaskFailed { task, source } => write!(f, "Task '{}' falhou: {}", task, source),
+            AppError::TasksFailed(failures) => {
+                write!(f, "{} tasks falharam:", failures.len())?;
+                for failure in failures {
+                    write!(f, "\n  • {}", failure)?;
+                }
+                Ok(())
+            }
+            AppError::CommandFailed { command, status, stderr } => {
+                match status {
+                    Some(code) => write!(f, "Comando '{}' falhou com código {}", command, code)?,
+                    None => write!(f, "Comando '{}' foi interrompido por um sinal", command)?,
+                }
+                if !stderr.trim().is_empty() {
+                    write!(f, ":\n{}", stderr.trim_end())?;
+                }
+                Ok(())
+            }
+            AppError::Io { context, source } => write!(f, "{}: {}", context, source),
+            AppError::Timeout { command, timeout } => write!(
+                f,
+                "Comando '{}' excedeu o tempo limite de {:.1}s e foi encerrado",
+                command,
+                timeout.as_secs_f64()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AppError::TaskFailed { source, .. } => Some(source.as_ref()),
+            AppError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<ConfigError> for AppError {
+    fn from(err: ConfigError) -> Self {
+        match err {
+            ConfigError::DependencyCycle(cycle) => AppError::DependencyCycle(cycle),
+            err => AppError::Config(err),
         }
     }
 }
 
-impl std::error::Error for AppError {}
+impl From<TaskError> for AppError {
+    fn from(err: TaskError) -> Self {
+        match err {
+            TaskError::TaskNotFound(task, available) => AppError::TaskNotFound { task, available },
+        }
+    }
+}
 
-impl From<crate::config::ConfigError> for AppError {
-    fn from(err: crate::config::ConfigError) -> Self {
-        AppError::Config(err)
+impl From<ParamErrors> for AppError {
+    fn from(errors: ParamErrors) -> Self {
+        AppError::InvalidParams(errors)
     }
-}
\ No newline at end of file
+}
@@ -1,97 +1,213 @@
-// mod config;
-// mod registry;
-// mod runner;
-// mod task;
-// mod tasks;
 mod config;
+mod diagnostics;
 mod errors;
+mod output;
 mod params;
+mod subprocess;
 mod runner;
 mod registry;
 mod task;
 mod tasks;
+#[cfg(test)]
+mod test_utils;
 
 use cliparser::{App, Command, Flag, FlagType, flag::FlagValue, ui::ColoredUI};
+use config::Config;
 use runner::TaskRunner;
-use std::{env, process};
+use std::{env, path::Path, process};
 
 use crate::errors::AppError;
 
 fn main() {
-    let app = App::new("runner", env!("CARGO_PKG_VERSION")).add_command(
-        Command::new("run")
-            .add_flag(
-                Flag::new("config", FlagType::String)
-                    .default_value(FlagValue::String("tasks.toml".to_string())),
-            )
-            .show_help_on_empty(false),
-    );
+    let app = App::new("runner", env!("CARGO_PKG_VERSION"))
+        .add_command(
+            Command::new("run")
+                .add_flag(config_flag())
+                .add_flag(Flag::new("profile", FlagType::String))
+                .add_flag(Flag::new("workdir", FlagType::String))
+                .add_flag(Flag::new("keep-going", FlagType::Bool))
+                .add_flag(Flag::new("timeout", FlagType::String))
+                .add_flag(
+                    Flag::new("jobs", FlagType::Integer)
+                        .default_value(FlagValue::Integer(1)),
+                )
+                .add_flag(Flag::new("enable", FlagType::List))
+                .add_flag(Flag::new("disable", FlagType::List))
+                .add_flag(Flag::new("param", FlagType::List))
+                .add_flag(Flag::new("set", FlagType::List))
+                .show_help_on_empty(false),
+        )
+        .add_command(
+            Command::new("validate")
+                .add_flag(config_flag())
+                .show_help_on_empty(false),
+        )
+        .add_command(
+            Command::new("list")
+                .add_flag(config_flag())
+                .show_help_on_empty(false),
+        )
+        .add_command(
+            Command::new("describe")
+                .add_flag(config_flag())
+                .add_flag(Flag::new("profile", FlagType::String)),
+        );
 
     match app.run_from_env() {
         Ok(parsed) => {
-            let config_path = parsed
-                .get_flag("config")
-                .and_then(|v| v.as_string())
-                .unwrap_or("tasks.toml");
-
-            let mut runner: TaskRunner = TaskRunner::new(config_path).unwrap_or_else(|err| {
-                eprintln!("❌ Erro: {}", err);
-                process::exit(1);
+            let current_dir = env::current_dir().unwrap_or_else(|source| {
+                exit_with_error(AppError::Io {
+                    context: "Não foi possível obter o diretório atual".to_string(),
+                    source,
+                })
             });
-            
+            let config_path = Config::locate(
+                parsed.get_flag("config").and_then(|v| v.as_string()),
+                &current_dir,
+            )
+            .unwrap_or_else(|err| exit_with_error(err));
+            let profile = parsed.get_flag("profile").and_then(|v| v.as_string());
+
+            let mut runner: TaskRunner =
+                TaskRunner::new(&config_path.to_string_lossy(), profile).unwrap_or_else(|err| exit_with_error(err));
+
             // Registra as tasks disponíveis
             runner.register_tasks();
-            runner.run_all();
 
+            let result = match parsed.command() {
+                "validate" => runner.validate(),
+                "list" => {
+                    runner.list_configured_tasks();
+                    Ok(())
+                }
+                "describe" => match parsed.args().first() {
+                    Some(task_name) => runner.describe_task(task_name),
+                    None => exit_with_error(AppError::Usage(
+                        "Informe a task a ser descrita: runner describe <task>".to_string(),
+                    )),
+                },
+                _ => {
+                    let jobs = parsed
+                        .get_flag("jobs")
+                        .and_then(|v| v.as_integer())
+                        .unwrap_or(1);
+
+                    if jobs < 1 {
+                        exit_with_error(AppError::Usage("--jobs deve ser maior que zero".to_string()));
+                    }
+                    runner.set_jobs(jobs as usize);
+                    runner.set_keep_going(
+                        parsed.get_flag("keep-going").and_then(|v| v.as_bool()).unwrap_or(false),
+                    );
+
+                    // Tempo limite das tasks que não definem `timeout` (ex: `--timeout 5m`)
+                    if let Some(timeout) = parsed.get_flag("timeout").and_then(|v| v.as_string()) {
+                        match config::parse_duration_str(timeout).filter(|timeout| !timeout.is_zero()) {
+                            Some(timeout) => runner.set_timeout(Some(timeout)),
+                            None => exit_with_error(AppError::Usage(format!(
+                                "--timeout inválido: '{}'. Use uma duração como \"30s\" ou \"5m\"",
+                                timeout
+                            ))),
+                        }
+                    }
+
+                    // As tasks executam a partir do diretório do arquivo de configuração,
+                    // exceto quando `--workdir` é informado
+                    let workdir = match parsed.get_flag("workdir").and_then(|v| v.as_string()) {
+                        Some(workdir) => Some(Path::new(workdir)),
+                        None => config_path.parent().filter(|dir| !dir.as_os_str().is_empty()),
+                    };
+                    if let Some(workdir) = workdir
+                        && let Err(source) = env::set_current_dir(workdir)
+                    {
+                        exit_with_error(AppError::Io {
+                            context: format!(
+                                "Não foi possível usar '{}' como diretório de trabalho",
+                                workdir.display()
+                            ),
+                            source,
+                        });
+                    }
+
+                    // Sobrescreve o `enabled` definido no arquivo de configuração
+                    for (flag, enabled) in [("enable", true), ("disable", false)] {
+                        let task_names = parsed
+                            .get_flag(flag)
+                            .and_then(|v| v.as_list())
+                            .unwrap_or(&[]);
+
+                        for task_name in task_names {
+                            if let Err(err) = runner.set_enabled(task_name, enabled) {
+                                exit_with_error(err);
+                            }
+                        }
+                    }
+
+                    let task_names = parsed.args();
+
+                    // Sobrescreve parâmetros: `--param task.chave=valor` e, com uma única
+                    // task selecionada, `--set chave=valor`
+                    let overrides = |flag: &str| -> &[String] {
+                        parsed.get_flag(flag).and_then(|v| v.as_list()).unwrap_or(&[])
+                    };
+
+                    for assignment in overrides("param") {
+                        let (key, value) = Config::parse_assignment(assignment)
+                            .unwrap_or_else(|err| exit_with_error(AppError::Usage(err.to_string())));
+                        let Some((task_name, param)) = key.rsplit_once('.') else {
+                            exit_with_error(AppError::Usage(format!(
+                                "--param deve ter o formato task.parametro=valor: '{}'",
+                                assignment
+                            )));
+                        };
+
+                        if let Err(err) = runner.set_param(task_name, param, value) {
+                            exit_with_error(err);
+                        }
+                    }
+
+                    let assignments = overrides("set");
+                    if !assignments.is_empty() && task_names.len() != 1 {
+                        exit_with_error(AppError::Usage(
+                            "--set exige que exatamente uma task seja informada".to_string(),
+                        ));
+                    }
+
+                    for assignment in assignments {
+                        let (param, value) = Config::parse_assignment(assignment)
+                            .unwrap_or_else(|err| exit_with_error(AppError::Usage(err.to_string())));
+
+                        if let Err(err) = runner.set_param(&task_names[0], &param, value) {
+                            exit_with_error(err);
+                        }
+                    }
+
+                    // Sem argumentos executa todas as tasks; caso contrário apenas as informadas
+                    if task_names.is_empty() {
+                        runner.run_all()
+                    } else {
+                        runner.run_tasks(task_names)
+                    }
+                }
+            };
+
+            if let Err(err) = result {
+                exit_with_error(err);
+            }
         }
-        Err(_) => process::exit(1),
+        Err(_) => process::exit(errors::EXIT_USAGE),
     }
 }
 
-// fn main() {
-//     if let Err(e) = run() {
-//         eprintln!("❌ Erro: {}", e);
-//         std::process::exit(1);
-//     }
-// }
-
-// fn run() -> anyhow::Result<()> {
-//     let args: Vec<String> = env::args().collect();
-
-//     // Cria o runner e registra as tasks
-//     let mut runner = TaskRunner::new("tasks.toml")?;
-//     runner.register_tasks();
-
-//     // Processa argumentos
-//     match args.len() {
-//         1 => {
-//             // Sem argumentos: lista as tasks
-//             println!("\n🎯 Task Runner em Rust\n");
-//             runner.list_configured_tasks();
-//             println!("Uso:");
-//             println!("  {} <task-name>  - Executa uma task específica", args[0]);
-//             println!("  {} --all        - Executa todas as tasks habilitadas", args[0]);
-//             println!("  {} --list       - Lista as tasks configuradas\n", args[0]);
-//         }
-//         2 => {
-//             let command = &args[1];
-//             match command.as_str() {
-//                 "--list" => {
-//                     runner.list_configured_tasks();
-//                 }
-//                 "--all" => {
-//                     runner.run_all()?;
-//                 }
-//                 task_name => {
-//                     runner.run_task(task_name)?;
-//                 }
-//             }
-//         }
-//         _ => {
-//             eprintln!("Uso: {} [task-name|--all|--list]", args[0]);
-//             std::process::exit(1);
-//         }
-//     }
-
-//     Ok(())
-// }
+/// Flag `--config`, comum a todos os comandos. Sem ela o arquivo é procurado a partir
+/// do diretório atual (veja `Config::locate`)
+fn config_flag() -> Flag {
+    Flag::new("config", FlagType::String)
+}
+
+/// Encerra o processo com o código de saída correspondente ao erro (veja `errors`)
+fn exit_with_error(err: impl Into<AppError>) -> ! {
+    let err = err.into();
+    eprintln!("❌ Erro: {}", err);
+    process::exit(err.exit_code());
+}
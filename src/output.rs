@@ -0,0 +1,96 @@
+//! Saída das tasks.
+//!
+//! Quando as tasks rodam em paralelo, cada uma escreve em um buffer próprio que
+//! só é impresso quando ela termina, evitando que as linhas de tasks diferentes
+//! se misturem no terminal.
+
+use std::{
+    cell::RefCell,
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+/// Stream de destino de uma linha de saída
+#[derive(Debug, Clone, Copy)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Linhas capturadas durante a execução de uma task
+pub type OutputLines = Vec<(Stream, String)>;
+
+type OutputBuffer = Arc<Mutex<OutputLines>>;
+
+thread_local! {
+    static CURRENT: RefCell<Option<OutputBuffer>> = const { RefCell::new(None) };
+}
+
+/// Executa `f` acumulando em memória tudo que for escrito com `task_println!`
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, OutputLines) {
+    let buffer = OutputBuffer::default();
+
+    CURRENT.with(|current| *current.borrow_mut() = Some(buffer.clone()));
+    let result = f();
+    CURRENT.with(|current| *current.borrow_mut() = None);
+
+    let lines = std::mem::take(&mut *buffer.lock().unwrap_or_else(|err| err.into_inner()));
+    (result, lines)
+}
+
+/// Destino da saída de uma task, que pode ser repassado a outras threads
+/// (ex: leitura da saída de processos filhos)
+#[derive(Clone)]
+pub struct Sink(Option<OutputBuffer>);
+
+impl Sink {
+    /// Se a saída está sendo acumulada em um buffer (em vez de ir direto para o terminal)
+    pub fn is_captured(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Escreve uma linha no buffer capturado ou, se não houver captura, direto no terminal
+    pub fn print(&self, stream: Stream, line: &str) {
+        match &self.0 {
+            Some(buffer) => buffer
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .push((stream, line.to_string())),
+            None => flush(&[(stream, line.to_string())]),
+        }
+    }
+}
+
+/// Destino da saída da thread atual
+pub fn sink() -> Sink {
+    Sink(CURRENT.with(|current| current.borrow().clone()))
+}
+
+/// Escreve uma linha no destino de saída da thread atual
+pub fn print(stream: Stream, line: &str) {
+    sink().print(stream, line);
+}
+
+/// Imprime linhas capturadas anteriormente, mantendo a ordem original
+pub fn flush(lines: &[(Stream, String)]) {
+    let stdout = std::io::stdout();
+    let stderr = std::io::stderr();
+    let mut out = stdout.lock();
+    let mut err = stderr.lock();
+
+    for (stream, line) in lines {
+        let _ = match stream {
+            Stream::Stdout => writeln!(out, "{}", line),
+            Stream::Stderr => writeln!(err, "{}", line),
+        };
+    }
+}
+
+/// `println!` que respeita a captura de saída das tasks
+macro_rules! task_println {
+    ($($arg:tt)*) => {
+        $crate::output::print($crate::output::Stream::Stdout, &format!($($arg)*))
+    };
+}
+
+pub(crate) use task_println;
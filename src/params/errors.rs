@@ -0,0 +1,57 @@
+use core::fmt;
+
+use super::ParamType;
+
+/// Problema encontrado ao validar um parâmetro
+#[derive(Debug, Clone)]
+pub enum ParamErrorKind {
+    Missing,
+    TypeMismatch { expected: ParamType, actual: String },
+    /// Item de um Array (`[1]`) ou valor de uma Table (`.CHAVE`) com tipo inválido
+    ItemTypeMismatch { item: String, expected: ParamType, actual: String },
+    Unknown { suggestion: Option<String> },
+}
+
+#[derive(Debug, Clone)]
+pub struct ParamError {
+    pub param: String,
+    pub kind: ParamErrorKind,
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ParamErrorKind::Missing => {
+                write!(f, "Parâmetro obrigatório '{}' não foi fornecido", self.param)
+            }
+            ParamErrorKind::TypeMismatch { ref expected, ref actual } => write!(
+                f,
+                "Parâmetro '{}' tem tipo inválido. Esperado: {:?}, Recebido: {}",
+                self.param, expected, actual
+            ),
+            ParamErrorKind::ItemTypeMismatch { ref item, ref expected, ref actual } => write!(
+                f,
+                "Parâmetro '{}{}' tem tipo inválido. Esperado: {:?}, Recebido: {}",
+                self.param, item, expected, actual
+            ),
+            ParamErrorKind::Unknown { ref suggestion } => {
+                write!(f, "Parâmetro desconhecido '{}'.", self.param)?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, " Você quis dizer '{}'?", suggestion)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Todos os problemas encontrados ao validar os parâmetros de uma task
+#[derive(Debug, Clone)]
+pub struct ParamErrors(pub Vec<ParamError>);
+
+impl fmt::Display for ParamErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let messages: Vec<String> = self.0.iter().map(|err| err.to_string()).collect();
+        write!(f, "{}", messages.join("\n"))
+    }
+}
@@ -1,6 +1,7 @@
 mod errors;
 
-use crate::errors::AppError;
+pub use errors::{ParamError, ParamErrorKind, ParamErrors};
+
 use std::collections::HashMap;
 use toml::Value;
 
@@ -12,6 +13,9 @@ pub enum ParamType {
     Integer,
     Float,
     Array,
+    Table,
+    /// String, número ou Bool. Usado para os itens de Arrays e Tables (`items`)
+    Scalar,
 }
 
 /// Definição de um parâmetro
@@ -22,6 +26,8 @@ pub struct ParamDefinition {
     pub required: bool,
     pub default: Option<Value>,
     pub description: String,
+    /// Tipo esperado de cada item, para parâmetros Array e Table
+    pub items: Option<ParamType>,
 }
 
 impl ParamDefinition {
@@ -32,6 +38,7 @@ impl ParamDefinition {
             required: false,
             default: None,
             description: String::new(),
+            items: None,
         }
     }
 
@@ -69,78 +76,165 @@ impl ParamDefinition {
         self.description = desc.into();
         self
     }
+
+    /// Exige que cada item do Array (ou cada valor da Table) tenha o tipo `item_type`
+    pub fn items(mut self, item_type: ParamType) -> Self {
+        self.items = Some(item_type);
+        self
+    }
 }
 
-/// Container para parâmetros validados
+/// Parâmetros validados e resolvidos (valores fornecidos + defaults)
 #[derive(Debug, Clone)]
 pub struct TaskParams {
-    definitions: Vec<ParamDefinition>,
-    raw_params: HashMap<String, Value>,
     params: HashMap<String, Value>,
 }
 
 impl TaskParams {
-    /// Cria um novo container de parâmetros a partir das definições e valores brutos
-    pub fn new(definitions: Vec<ParamDefinition>, raw_params: HashMap<String, Value>) -> Self {
-        Self {
-            definitions,
-            raw_params,
-            params: HashMap::new(),
-        }
+    /// Valida os valores brutos contra as definições e resolve os defaults.
+    ///
+    /// Parâmetros opcionais sem valor e sem default ficam ausentes e chaves sem
+    /// definição correspondente são rejeitadas. Todos os problemas encontrados
+    /// são reportados juntos.
+    pub fn new(
+        definitions: &[ParamDefinition],
+        raw_params: &HashMap<String, Value>,
+    ) -> Result<Self, ParamErrors> {
+        Self::resolve(definitions, raw_params, true)
+    }
+
+    /// Como `new`, mas aceita chaves sem definição, repassando-as sem validação.
+    /// Usado por tasks que aceitam parâmetros livres.
+    pub fn permissive(
+        definitions: &[ParamDefinition],
+        raw_params: &HashMap<String, Value>,
+    ) -> Result<Self, ParamErrors> {
+        Self::resolve(definitions, raw_params, false)
     }
 
-    pub fn validate(&self) -> Result<(), AppError> {
+    fn resolve(
+        definitions: &[ParamDefinition],
+        raw_params: &HashMap<String, Value>,
+        strict: bool,
+    ) -> Result<Self, ParamErrors> {
         let mut params = HashMap::new();
+        let mut errors = Vec::new();
 
-        // Valida cada definição de parâmetro
-        for def in &self.definitions {
-            let value = match self.raw_params.get(&def.name) {
+        for def in definitions {
+            let value = match raw_params.get(&def.name) {
                 Some(v) => {
                     // Valida o tipo
-                    Self::validate_type(&def.name, v, &def.param_type)?;
+                    if let Err(err) = Self::validate_type(&def.name, v, &def.param_type) {
+                        errors.push(err);
+                        continue;
+                    }
+                    if let Some(item_type) = &def.items
+                        && let Err(err) = Self::validate_items(&def.name, v, item_type)
+                    {
+                        errors.push(err);
+                        continue;
+                    }
                     v.clone()
                 }
                 None => {
-                    // Se não foi fornecido, usa o default ou retorna erro se for required
+                    // Se não foi fornecido, usa o default ou registra erro se for required
                     if def.required {
-                        return Err(AppError::Generic(format!(
-                            "Parâmetro obrigatório '{}' não foi fornecido",
-                            def.name
-                        )));
+                        errors.push(ParamError {
+                            param: def.name.clone(),
+                            kind: ParamErrorKind::Missing,
+                        });
+                        continue;
+                    }
+                    match &def.default {
+                        Some(default) => default.clone(),
+                        None => continue,
                     }
-                    def.default.clone().unwrap_or(Value::String(String::new()))
                 }
             };
 
             params.insert(def.name.clone(), value);
         }
 
-        Ok(())
+        // Chaves que não correspondem a nenhuma definição
+        let mut unknown: Vec<&String> = raw_params
+            .keys()
+            .filter(|key| !definitions.iter().any(|def| &def.name == *key))
+            .collect();
+        unknown.sort();
+
+        let names: Vec<&str> = definitions.iter().map(|def| def.name.as_str()).collect();
+        for key in unknown {
+            if strict {
+                errors.push(ParamError {
+                    param: key.clone(),
+                    kind: ParamErrorKind::Unknown {
+                        suggestion: suggest(key, &names).map(|s| s.to_string()),
+                    },
+                });
+            } else {
+                params.insert(key.clone(), raw_params[key].clone());
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(ParamErrors(errors));
+        }
+
+        Ok(Self { params })
     }
 
     /// Valida se o valor corresponde ao tipo esperado
-    fn validate_type(name: &str, value: &Value, expected_type: &ParamType) -> Result<(), AppError> {
-        let matches = match (value, expected_type) {
-            (Value::String(_), ParamType::String) => true,
-            (Value::Boolean(_), ParamType::Bool) => true,
-            (Value::Integer(_), ParamType::Integer) => true,
-            (Value::Float(_), ParamType::Float) => true,
-            (Value::Array(_), ParamType::Array) => true,
-            _ => false,
-        };
-
-        if !matches {
-            return Err(AppError::Generic(format!(
-                "Parâmetro '{}' tem tipo inválido. Esperado: {:?}, Recebido: {:?}",
-                name,
-                expected_type,
-                Self::value_type_name(value)
-            )));
+    fn validate_type(name: &str, value: &Value, expected_type: &ParamType) -> Result<(), ParamError> {
+        if !Self::matches_type(value, expected_type) {
+            return Err(ParamError {
+                param: name.to_string(),
+                kind: ParamErrorKind::TypeMismatch {
+                    expected: expected_type.clone(),
+                    actual: Self::value_type_name(value).to_string(),
+                },
+            });
         }
 
         Ok(())
     }
 
+    fn matches_type(value: &Value, expected_type: &ParamType) -> bool {
+        matches!(
+            (value, expected_type),
+            (Value::String(_), ParamType::String)
+                | (Value::Boolean(_), ParamType::Bool)
+                | (Value::Integer(_), ParamType::Integer)
+                | (Value::Float(_), ParamType::Float)
+                | (Value::Array(_), ParamType::Array)
+                | (Value::Table(_), ParamType::Table)
+                | (
+                    Value::String(_) | Value::Boolean(_) | Value::Integer(_) | Value::Float(_),
+                    ParamType::Scalar
+                )
+        )
+    }
+
+    /// Valida o tipo de cada item de um Array ou valor de uma Table, reportando o primeiro inválido
+    fn validate_items(name: &str, value: &Value, item_type: &ParamType) -> Result<(), ParamError> {
+        let items: Vec<(String, &Value)> = match value {
+            Value::Array(items) => items.iter().enumerate().map(|(i, item)| (format!("[{}]", i), item)).collect(),
+            Value::Table(table) => table.iter().map(|(key, item)| (format!(".{}", key), item)).collect(),
+            _ => Vec::new(),
+        };
+
+        match items.into_iter().find(|(_, item)| !Self::matches_type(item, item_type)) {
+            Some((item, value)) => Err(ParamError {
+                param: name.to_string(),
+                kind: ParamErrorKind::ItemTypeMismatch {
+                    item,
+                    expected: item_type.clone(),
+                    actual: Self::value_type_name(value).to_string(),
+                },
+            }),
+            None => Ok(()),
+        }
+    }
+
     fn value_type_name(value: &Value) -> &str {
         match value {
             Value::String(_) => "String",
@@ -161,6 +255,16 @@ impl TaskParams {
             .map(|s| s.to_string())
     }
 
+    /// Obtém um parâmetro obrigatório como String
+    pub fn require_string(&self, key: &str) -> Result<String, ParamErrors> {
+        self.get_string(key).ok_or_else(|| {
+            ParamErrors(vec![ParamError {
+                param: key.to_string(),
+                kind: ParamErrorKind::Missing,
+            }])
+        })
+    }
+
     /// Obtém um parâmetro como String com valor padrão
     pub fn get_string_or(&self, key: &str, default: &str) -> String {
         self.get_string(key).unwrap_or_else(|| default.to_string())
@@ -201,6 +305,11 @@ impl TaskParams {
         self.params.get(key).and_then(|v| v.as_array())
     }
 
+    /// Obtém um parâmetro como Table
+    pub fn get_table(&self, key: &str) -> Option<&toml::Table> {
+        self.params.get(key).and_then(|v| v.as_table())
+    }
+
     /// Verifica se um parâmetro existe
     pub fn has(&self, key: &str) -> bool {
         self.params.contains_key(key)
@@ -212,5 +321,36 @@ impl TaskParams {
     }
 }
 
+/// Sugere o nome mais parecido com `name` entre `candidates`, se houver algum próximo o suficiente
+pub fn suggest<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(2);
+
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Distância de Levenshtein entre duas strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
 #[cfg(test)]
 mod tests;
@@ -68,3 +68,42 @@ fn test_valid_params() {
     assert_eq!(params.get_int("age").unwrap(), 30);
     assert_eq!(params.get_bool("active").unwrap(), true);
 }
+
+#[test]
+fn test_optional_param_without_default_is_absent() {
+    let definitions = vec![ParamDefinition::new("message", ParamType::String).optional()];
+
+    let raw_params = HashMap::new();
+    let params = TaskParams::new(&definitions, &raw_params).unwrap();
+
+    assert!(!params.has("message"));
+    assert_eq!(params.get_string("message"), None);
+}
+
+#[test]
+fn test_unknown_param_suggestion() {
+    let definitions = vec![ParamDefinition::new("push", ParamType::Bool)];
+
+    let mut raw_params = HashMap::new();
+    raw_params.insert("pussh".to_string(), Value::Boolean(true));
+
+    let err = TaskParams::new(&definitions, &raw_params).unwrap_err();
+    assert!(err.to_string().contains("Você quis dizer 'push'?"));
+}
+
+#[test]
+fn test_permissive_keeps_unknown_params() {
+    let definitions = vec![ParamDefinition::new("push", ParamType::Bool)];
+
+    let mut raw_params = HashMap::new();
+    raw_params.insert("extra".to_string(), Value::Integer(1));
+
+    let params = TaskParams::permissive(&definitions, &raw_params).unwrap();
+    assert_eq!(params.get_int("extra"), Some(1));
+}
+
+#[test]
+fn test_suggest() {
+    assert_eq!(suggest("tga", &["tag", "message"]), Some("tag"));
+    assert_eq!(suggest("xyz", &["tag", "message"]), None);
+}
@@ -1,5 +1,8 @@
 mod errors;
-use crate::{registry::errors::TaskError, task::{Task, TaskFactory}};
+
+pub use errors::TaskError;
+
+use crate::task::{Task, TaskFactory};
 use std::collections::HashMap;
 
 /// Registro central de todas as tasks disponíveis
@@ -1,19 +1,73 @@
-// use crate::config::{Config, TaskConfig};
-// use crate::registry::{TaskError, TaskRegistry};
-use crate::{config::Config, errors::AppError, registry::TaskRegistry, task::Task};
+use crate::{
+    config::{Config, ConfigError, FailurePolicy, TaskConfig},
+    diagnostics::{Diagnostic, Diagnostics},
+    errors::AppError,
+    output::{self, OutputLines, task_println},
+    registry::{TaskError, TaskRegistry},
+    subprocess,
+    task::Task,
+};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
 
 pub struct TaskRunner {
     registry: TaskRegistry,
     config: Config,
+    jobs: usize,
+    /// Trata `on_failure = "stop"` como `"continue"` (`--keep-going`)
+    keep_going: bool,
+    /// Tempo limite das tasks que não definem `timeout` (`--timeout`)
+    timeout: Option<Duration>,
 }
 
+/// Resultado da execução de uma task
+enum TaskOutcome {
+    Success(Duration),
+    Failed(Duration, AppError),
+    /// Falhou, mas com `on_failure = "ignore"`
+    Ignored(Duration),
+    /// Desabilitada na configuração
+    Skipped,
+    /// Não executada porque a dependência informada falhou
+    Blocked(String),
+}
+
+/// Mensagem enviada pelas threads de execução ao terminar uma task
+type Completion<'a> = (&'a str, Duration, Result<(), AppError>, OutputLines);
+
 impl TaskRunner {
-    pub fn new(config_path: &str) -> Result<Self, AppError> {
-        let config = Config::from_file(config_path)?;
+    pub fn new(config_path: &str, profile: Option<&str>) -> Result<Self, AppError> {
+        let config = Config::from_file(config_path, profile)?;
 
         let registry = TaskRegistry::new();
         
-        Ok(TaskRunner { config, registry })
+        Ok(TaskRunner {
+            config,
+            registry,
+            jobs: 1,
+            keep_going: false,
+            timeout: None,
+        })
+    }
+
+    /// Define quantas tasks independentes podem executar ao mesmo tempo
+    pub fn set_jobs(&mut self, jobs: usize) {
+        self.jobs = jobs.max(1);
+    }
+
+    /// Continua executando as tasks independentes mesmo depois de uma falha
+    pub fn set_keep_going(&mut self, keep_going: bool) {
+        self.keep_going = keep_going;
+    }
+
+    /// Define o tempo limite de cada tentativa das tasks que não configuram `timeout`
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
     }
 
     /// Registra automaticamente todas as tasks disponíveis
@@ -25,105 +79,618 @@ impl TaskRunner {
         }
     }
 
-    pub fn run_task(&self, task_name: &str) {
-        let task_config = self.config.find_task(task_name);
-        let task = self.registry.get(task_name).unwrap();
+    /// Executa as tasks informadas e, antes delas, as suas dependências
+    pub fn run_tasks(&self, task_names: &[String]) -> Result<(), AppError> {
+        for task_name in task_names {
+            self.resolve_task(task_name)?;
+        }
+
+        let roots: Vec<&str> = task_names.iter().map(|name| name.as_str()).collect();
+        let order = execution_order(&self.config, &roots)?;
+        self.validate_params(&order)?;
+        self.execute_graph(&order)
+    }
+
+    /// Sobrescreve o flag `enabled` de uma task configurada (`--enable`/`--disable`)
+    pub fn set_enabled(&mut self, task_name: &str, enabled: bool) -> Result<(), AppError> {
+        let not_found = self.task_not_found(task_name);
+        let task_config = self.config.tasks.get_mut(task_name).ok_or(not_found)?;
+        task_config.enabled = enabled;
+
+        Ok(())
+    }
+
+    /// Sobrescreve um parâmetro de uma task configurada (`--param`/`--set`)
+    pub fn set_param(&mut self, task_name: &str, key: &str, value: toml::Value) -> Result<(), AppError> {
+        self.task_config(task_name)?;
+        self.config.set_param(task_name, key, value)?;
+
+        Ok(())
+    }
+
+    /// Verifica se a task está configurada e se existe uma implementação registrada para o seu tipo
+    fn resolve_task(&self, task_name: &str) -> Result<(), AppError> {
+        let task_config = self.task_config(task_name)?;
+        self.registry.get(&task_config.task_type)?;
+        Ok(())
+    }
+
+    /// Configuração de uma task, ou erro de task não encontrada
+    fn task_config(&self, task_name: &str) -> Result<&TaskConfig, AppError> {
+        self.config
+            .find_task(task_name)
+            .ok_or_else(|| self.task_not_found(task_name).into())
+    }
+
+    /// Erro de task não encontrada listando as tasks configuradas
+    fn task_not_found(&self, task_name: &str) -> TaskError {
+        let mut configured: Vec<&str> = self.config.tasks.keys().map(|k| k.as_str()).collect();
+        configured.sort();
+
+        TaskError::TaskNotFound(task_name.to_string(), configured.join(", "))
+    }
+
+    /// Executa todas as tasks configuradas, respeitando as dependências
+    pub fn run_all(&self) -> Result<(), AppError> {
+        let mut roots: Vec<&str> = self.config.tasks.keys().map(|k| k.as_str()).collect();
+        roots.sort();
+
+        let order = execution_order(&self.config, &roots)?;
+        self.validate_params(&order)?;
+        self.execute_graph(&order)
+    }
+
+    /// Lista as tasks configuradas e as tasks disponíveis no sistema
+    pub fn list_configured_tasks(&self) {
+        println!("\n📋 Tasks configuradas:\n");
+
+        let mut tasks: Vec<(&String, &TaskConfig)> = self.config.tasks.iter().collect();
+        tasks.sort_by_key(|(name, _)| *name);
+
+        for (name, task_config) in tasks {
+            let status = if task_config.enabled { "✅" } else { "❌" };
+            let description = match self.registry.get(&task_config.task_type) {
+                Ok(task) => task.description().to_string(),
+                Err(_) => format!("(tipo '{}' não registrado)", task_config.task_type),
+            };
+
+            if task_config.task_type == *name {
+                println!("  {} {} - {}", status, name, description);
+            } else {
+                println!("  {} {} ({}) - {}", status, name, task_config.task_type, description);
+            }
+        }
+
+        println!("\n💡 Tasks disponíveis no sistema:");
+        for task_name in self.registry.list_tasks() {
+            match self.registry.get(&task_name) {
+                Ok(task) => println!("  • {} - {}", task_name, task.description()),
+                Err(_) => println!("  • {}", task_name),
+            }
+        }
+        println!();
+    }
+
+    /// Mostra a descrição e os parâmetros aceitos por uma task (configurada ou registrada)
+    pub fn describe_task(&self, task_name: &str) -> Result<(), AppError> {
+        let task_type = self
+            .config
+            .tasks
+            .get(task_name)
+            .map(|task_config| task_config.task_type.as_str())
+            .unwrap_or(task_name);
+
+        let task = self.registry.get(task_type)?;
+
+        println!("\n🔎 {}", task_name);
+        if task_type != task_name {
+            println!("   Tipo: {}", task_type);
+        }
+        if let Some(profile) = &self.config.profile {
+            println!("   Perfil: {}", profile);
+        }
+        println!("📝 {}\n", task.description());
+
+        let definitions = task.param_definitions();
+        if definitions.is_empty() {
+            println!("Esta task não aceita parâmetros.\n");
+            return Ok(());
+        }
+
+        // Valores efetivos da configuração, já com o perfil aplicado
+        let configured = self.config.tasks.get(task_name).map(|task_config| &task_config.params);
+
+        let rows: Vec<Vec<String>> = definitions
+            .iter()
+            .map(|def| {
+                vec![
+                    def.name.clone(),
+                    format!("{:?}", def.param_type),
+                    if def.required { "sim" } else { "não" }.to_string(),
+                    def.default
+                        .as_ref()
+                        .map(|value| value.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    configured
+                        .and_then(|params| params.get(&def.name))
+                        .map(|value| value.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    def.description.clone(),
+                ]
+            })
+            .collect();
+
+        print_table(&["Parâmetro", "Tipo", "Obrigatório", "Default", "Valor", "Descrição"], &rows);
+        println!();
+
+        Ok(())
+    }
+
+    /// Valida toda a configuração sem executar nenhuma task: tipos das tasks,
+    /// parâmetros (inclusive de tasks desabilitadas) e dependências
+    pub fn validate(&self) -> Result<(), AppError> {
+        let mut names: Vec<&String> = self.config.tasks.keys().collect();
+        names.sort();
+
+        let mut diagnostics = self.param_diagnostics(names.iter().copied(), true);
+
+        // Dependências que não existem
+        for name in &names {
+            let task_config = self.task_config(name)?;
+            for dependency in &task_config.depends_on {
+                if !self.config.tasks.contains_key(dependency) {
+                    let err = ConfigError::DependencyNotFound(name.to_string(), dependency.clone());
+                    diagnostics.push(Diagnostic::new(name, err.to_string(), task_config.location.clone()));
+                }
+            }
+        }
+
+        // Ciclos são procurados ignorando as dependências que não existem, já reportadas acima
+        let mut graph = Config::default();
+        graph.tasks = self
+            .config
+            .tasks
+            .iter()
+            .map(|(name, task_config)| {
+                let mut task_config = task_config.clone();
+                task_config.depends_on.retain(|dependency| self.config.tasks.contains_key(dependency));
+                (name.clone(), task_config)
+            })
+            .collect();
+
+        let roots: Vec<&str> = names.iter().map(|name| name.as_str()).collect();
+        if let Err(ConfigError::DependencyCycle(cycle)) = execution_order(&graph, &roots) {
+            let location = self.config.find_task(&cycle[0]).and_then(|task| task.location.clone());
+            let err = ConfigError::DependencyCycle(cycle.clone());
+            diagnostics.push(Diagnostic::new(&cycle[0], err.to_string(), location));
+        }
+
+        if !diagnostics.is_empty() {
+            return Err(AppError::Validation(Diagnostics(diagnostics)));
+        }
+
+        println!("✅ Configuração válida: {} task(s)", names.len());
+        Ok(())
+    }
+
+    /// Valida os parâmetros de todas as tasks habilitadas de `order` antes de executar
+    /// qualquer uma delas, reportando todos os problemas de uma só vez
+    fn validate_params(&self, order: &[String]) -> Result<(), AppError> {
+        let diagnostics = self.param_diagnostics(order.iter(), false);
 
-        task.execute(task_config).unwrap();
+        if diagnostics.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(Diagnostics(diagnostics)))
+        }
     }
 
-    pub fn run_all(&self) {
-        let enabled_tasks: Vec<&String> = self.config.tasks.keys().collect();
+    /// Coleta os problemas de tipo e de parâmetros das tasks informadas
+    fn param_diagnostics<'a>(
+        &self,
+        names: impl Iterator<Item = &'a String>,
+        include_disabled: bool,
+    ) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
 
-        for task_name in enabled_tasks {
-            self.run_task(task_name);
+        for name in names {
+            let Some(task_config) = self.config.find_task(name) else { continue };
+            if !task_config.enabled && !include_disabled {
+                continue;
+            }
+
+            match self.registry.get(&task_config.task_type) {
+                Ok(task) => {
+                    if let Err(errors) = task.resolve_params(task_config) {
+                        diagnostics.extend(
+                            errors
+                                .0
+                                .iter()
+                                .map(|error| Diagnostic::from_param_error(name, task_config, error)),
+                        );
+                    }
+                }
+                Err(err) => diagnostics.push(Diagnostic::new(
+                    name,
+                    err.to_string(),
+                    task_config.location.clone(),
+                )),
+            }
         }
+
+        diagnostics
     }
 
-    // /// Registra todas as tasks disponíveis
-    // pub fn register_tasks(&mut self) {
-    //     // Registra a task git-tag
-    //     self.registry.register("git-tag", || {
-    //         Box::new(crate::tasks::git_tag::GitTagTask::new())
-    //     });
+    /// Executa as tasks de `order` (já em ordem topológica) usando até `jobs` threads.
+    ///
+    /// Uma task só é iniciada quando todas as suas dependências terminaram com sucesso
+    /// (ou falharam com `on_failure = "ignore"`). Quando uma task falha, o `on_failure`
+    /// dela decide se nenhuma outra task é iniciada (`stop`) ou se as tasks que não
+    /// dependem dela continuam (`continue`). Com mais de um job, a saída de cada task é
+    /// acumulada e impressa de uma só vez quando ela termina.
+    fn execute_graph(&self, order: &[String]) -> Result<(), AppError> {
+        let buffered = self.jobs > 1;
+        let configs = order
+            .iter()
+            .map(|name| self.task_config(name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Quantas dependências cada task ainda aguarda e quem depende de quem
+        let mut pending: Vec<usize> = Vec::with_capacity(order.len());
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); order.len()];
+        let position: HashMap<&str, usize> = order
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.as_str(), i))
+            .collect();
+
+        for (i, task_config) in configs.iter().enumerate() {
+            let dependencies: HashSet<&String> = task_config.depends_on.iter().collect();
+            pending.push(dependencies.len());
+            for dependency in dependencies {
+                dependents[position[dependency.as_str()]].push(i);
+            }
+        }
+
+        let mut ready: BTreeSet<usize> = (0..order.len()).filter(|&i| pending[i] == 0).collect();
+        let mut outcomes: HashMap<usize, TaskOutcome> = HashMap::new();
 
-    //     // Adicione mais tasks aqui conforme as criar
-    //     // self.registry.register("build", || Box::new(crate::tasks::build::BuildTask::new()));
-    // }
+        thread::scope(|scope| {
+            let (sender, receiver) = mpsc::channel::<Completion>();
+            let mut running = 0;
+            let mut stopped = false;
 
-    // /// Lista todas as tasks configuradas
-    // pub fn list_configured_tasks(&self) {
-    //     println!("\n📋 Tasks configuradas:\n");
+            loop {
+                while !stopped && running < self.jobs {
+                    let Some(index) = ready.pop_first() else { break };
+                    let name = order[index].as_str();
+                    let task_config = configs[index];
 
-    //     for task in &self.config.tasks {
-    //         let status = if task.enabled { "✅" } else { "❌" };
-    //         println!("  {} {} - {}", status, task.name, task.description);
-    //     }
+                    // Tasks desabilitadas não executam, mas liberam quem depende delas
+                    if !task_config.enabled {
+                        println!("\n⏭️  Task '{}' desabilitada, pulando", name);
+                        outcomes.insert(index, TaskOutcome::Skipped);
+                        release_dependents(index, &dependents, &mut pending, &mut ready);
+                        continue;
+                    }
 
-    //     println!("\n💡 Tasks disponíveis no sistema:");
-    //     for task_name in self.registry.list_tasks() {
-    //         println!("  • {}", task_name);
-    //     }
-    //     println!();
-    // }
+                    let task = match self.registry.get(&task_config.task_type) {
+                        Ok(task) => task,
+                        Err(err) => {
+                            outcomes.insert(index, TaskOutcome::Failed(Duration::ZERO, err.into()));
+                            stopped = true;
+                            break;
+                        }
+                    };
+                    let sender = sender.clone();
+                    let timeout = task_config.timeout.or(self.timeout);
 
-    // /// Executa uma task específica
-    // pub fn run_task(&self, task_name: &str) -> Result<()> {
-    //     // Verifica se a task está configurada
-    //     let task_config = self
-    //         .config
-    //         .find_task(task_name)
-    //         .ok_or_else(|| {
-    //             anyhow::anyhow!(
-    //                 "Task '{}' não está configurada no arquivo tasks.toml",
-    //                 task_name
-    //             )
-    //         })?;
+                    if !buffered {
+                        println!("\n🚀 Executando task: {}", name);
+                    }
 
-    //     // Verifica se a task está habilitada
-    //     if !task_config.enabled {
-    //         return Err(TaskError::TaskDisabled(task_name.to_string()).into());
-    //     }
+                    scope.spawn(move || {
+                        let start = Instant::now();
+                        let execute = || execute_with_retries(task.as_ref(), name, task_config, timeout);
 
-    //     // Obtém a task do registro
-    //     let task = self.registry.get(task_name)?;
+                        let (result, lines) = if buffered {
+                            output::capture(execute)
+                        } else {
+                            (execute(), Vec::new())
+                        };
 
-    //     println!("\n🚀 Executando task: {}", task.name());
-    //     println!("📝 {}\n", task.description());
+                        let _ = sender.send((name, start.elapsed(), result, lines));
+                    });
+                    running += 1;
+                }
 
-    //     // Executa a task
-    //     task.execute(task_config)?;
+                if running == 0 {
+                    break;
+                }
 
-    //     println!("\n✨ Task '{}' concluída com sucesso!\n", task_name);
+                let Ok((name, elapsed, result, lines)) = receiver.recv() else { break };
+                running -= 1;
+
+                if buffered {
+                    println!("\n🚀 Task: {}", name);
+                    output::flush(&lines);
+                }
+
+                let index = position[name];
+                let err = match result {
+                    Ok(()) => {
+                        outcomes.insert(index, TaskOutcome::Success(elapsed));
+                        release_dependents(index, &dependents, &mut pending, &mut ready);
+                        continue;
+                    }
+                    Err(err) => err,
+                };
+
+                match configs[index].on_failure {
+                    FailurePolicy::Ignore => {
+                        println!("⚠️  Task '{}' falhou (ignorado): {}", name, err);
+                        outcomes.insert(index, TaskOutcome::Ignored(elapsed));
+                        release_dependents(index, &dependents, &mut pending, &mut ready);
+                    }
+                    FailurePolicy::Continue => {
+                        outcomes.insert(index, TaskOutcome::Failed(elapsed, err));
+                        block_dependents(index, order, &dependents, &mut outcomes);
+                    }
+                    FailurePolicy::Stop => {
+                        outcomes.insert(index, TaskOutcome::Failed(elapsed, err));
+                        block_dependents(index, order, &dependents, &mut outcomes);
+                        stopped = !self.keep_going;
+                    }
+                }
+            }
+        });
+
+        println!("{}\n", Self::summary(order, &outcomes));
+
+        // Erros das tasks que falharam, na ordem de execução
+        let mut failures: Vec<AppError> = (0..order.len())
+            .filter_map(|index| match outcomes.remove(&index) {
+                Some(TaskOutcome::Failed(_, err)) => Some(AppError::TaskFailed {
+                    task: order[index].clone(),
+                    source: Box::new(err),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        match failures.len() {
+            0 => Ok(()),
+            1 => Err(failures.remove(0)),
+            _ => Err(AppError::TasksFailed(failures)),
+        }
+    }
 
-    //     Ok(())
-    // }
+    /// Resumo da execução na ordem topológica das tasks, seguido do total de tasks que
+    /// passaram, falharam e foram puladas
+    fn summary(order: &[String], outcomes: &HashMap<usize, TaskOutcome>) -> String {
+        let mut lines = vec!["\n📊 Resumo:".to_string()];
 
-    // /// Executa todas as tasks habilitadas
-    // pub fn run_all(&self) -> Result<()> {
-    //     let enabled_tasks: Vec<&TaskConfig> = self
-    //         .config
-    //         .tasks
-    //         .iter()
-    //         .filter(|t| t.enabled)
-    //         .collect();
+        let (mut passed, mut failed, mut ignored, mut skipped) = (0, 0, 0, 0);
+        for (index, name) in order.iter().enumerate() {
+            let line = match outcomes.get(&index) {
+                Some(TaskOutcome::Success(elapsed)) => {
+                    passed += 1;
+                    format!("  ✅ {} ({:.2}s)", name, elapsed.as_secs_f64())
+                }
+                Some(TaskOutcome::Failed(elapsed, _)) => {
+                    failed += 1;
+                    format!("  ❌ {} ({:.2}s)", name, elapsed.as_secs_f64())
+                }
+                Some(TaskOutcome::Ignored(elapsed)) => {
+                    ignored += 1;
+                    format!("  ⚠️  {} ({:.2}s, falha ignorada)", name, elapsed.as_secs_f64())
+                }
+                Some(TaskOutcome::Skipped) => {
+                    skipped += 1;
+                    format!("  ⏭️  {} (desabilitada)", name)
+                }
+                Some(TaskOutcome::Blocked(dependency)) => {
+                    skipped += 1;
+                    format!("  ⏭️  {} (dependência '{}' falhou)", name, dependency)
+                }
+                None => {
+                    skipped += 1;
+                    format!("  ⏭️  {} (não executada)", name)
+                }
+            };
+            lines.push(line);
+        }
 
-    //     if enabled_tasks.is_empty() {
-    //         println!("⚠️  Nenhuma task habilitada para executar");
-    //         return Ok(());
-    //     }
+        let mut total = format!("\n  Total: {} sucesso(s), {} falha(s), {} pulada(s)", passed, failed, skipped);
+        if ignored > 0 {
+            total.push_str(&format!(", {} falha(s) ignorada(s)", ignored));
+        }
+        lines.push(total);
 
-    //     println!("\n🚀 Executando {} task(s) habilitada(s)...\n", enabled_tasks.len());
+        lines.join("\n")
+    }
+}
 
-    //     for task_config in enabled_tasks {
-    //         if let Err(e) = self.run_task(&task_config.name) {
-    //             eprintln!("❌ Erro ao executar task '{}': {}", task_config.name, e);
-    //             return Err(e);
-    //         }
-    //     }
+/// Imprime uma tabela com colunas alinhadas
+fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
 
-    //     println!("✨ Todas as tasks foram concluídas!\n");
+    let format_row = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
 
-    //     Ok(())
-    // }
+    println!("{}", format_row(headers.to_vec()));
+    println!(
+        "{}",
+        widths.iter().map(|w| "─".repeat(*w)).collect::<Vec<_>>().join("  ")
+    );
+    for row in rows {
+        println!("{}", format_row(row.iter().map(|c| c.as_str()).collect()));
+    }
 }
+
+/// Executa a task e, se ela falhar com um erro recuperável, tenta novamente conforme
+/// o `retry` da configuração. Retorna o erro da última tentativa.
+///
+/// O `timeout` vale para cada tentativa: os processos iniciados pela task que ainda
+/// estiverem rodando quando ele se esgota são encerrados e a tentativa falha. Ele só
+/// é verificado enquanto a task espera por processos; o código da própria task não é
+/// interrompido.
+fn execute_with_retries(
+    task: &dyn Task,
+    name: &str,
+    task_config: &TaskConfig,
+    timeout: Option<Duration>,
+) -> Result<(), AppError> {
+    let policy = &task_config.retry;
+    let attempts = policy.retries + 1;
+    let mut attempt = 1;
+
+    loop {
+        let attempt_result = subprocess::with_timeout(timeout, || {
+            panic::catch_unwind(AssertUnwindSafe(|| task.execute(task_config)))
+        });
+
+        let err = match attempt_result {
+            Ok(Ok(())) => return Ok(()),
+            Ok(Err(err)) => err,
+            // Um pânico indica um bug na task, que não se resolve tentando de novo
+            Err(_) => return Err(AppError::Generic(format!("Task '{}' entrou em pânico", name))),
+        };
+
+        if attempt >= attempts {
+            if attempts > 1 {
+                task_println!("❌ Task '{}' falhou após {} tentativas", name, attempts);
+            }
+            return Err(err);
+        }
+
+        if !task.is_retryable(&err) {
+            task_println!("❌ Task '{}' falhou com um erro permanente, sem novas tentativas", name);
+            return Err(err);
+        }
+
+        let delay = policy.delay_before(attempt);
+        task_println!(
+            "🔁 Tentativa {}/{} de '{}' falhou: {}\n   Nova tentativa em {:.1}s",
+            attempt,
+            attempts,
+            name,
+            err,
+            delay.as_secs_f64()
+        );
+        thread::sleep(delay);
+        attempt += 1;
+    }
+}
+
+/// Marca como bloqueadas todas as tasks que dependem, direta ou indiretamente, da
+/// task `index`, que falhou
+fn block_dependents(
+    index: usize,
+    order: &[String],
+    dependents: &[Vec<usize>],
+    outcomes: &mut HashMap<usize, TaskOutcome>,
+) {
+    let mut stack = dependents[index].clone();
+    while let Some(dependent) = stack.pop() {
+        if outcomes.contains_key(&dependent) {
+            continue;
+        }
+        outcomes.insert(dependent, TaskOutcome::Blocked(order[index].clone()));
+        stack.extend(&dependents[dependent]);
+    }
+}
+
+/// Marca a task `index` como concluída, liberando as dependentes que não aguardam mais nada
+fn release_dependents(
+    index: usize,
+    dependents: &[Vec<usize>],
+    pending: &mut [usize],
+    ready: &mut BTreeSet<usize>,
+) {
+    for &dependent in &dependents[index] {
+        pending[dependent] -= 1;
+        if pending[dependent] == 0 {
+            ready.insert(dependent);
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum VisitState {
+    Visiting,
+    Done,
+}
+
+/// Resolve a ordem de execução (topológica) das tasks a partir de `roots`.
+///
+/// Cada task aparece uma única vez, sempre depois de todas as suas dependências,
+/// mesmo quando é compartilhada por várias tasks.
+fn execution_order(config: &Config, roots: &[&str]) -> Result<Vec<String>, ConfigError> {
+    let mut order = Vec::new();
+    let mut states = HashMap::new();
+    let mut path = Vec::new();
+
+    for root in roots {
+        visit(config, root, &mut states, &mut path, &mut order)?;
+    }
+
+    Ok(order)
+}
+
+fn visit<'a>(
+    config: &'a Config,
+    task_name: &'a str,
+    states: &mut HashMap<&'a str, VisitState>,
+    path: &mut Vec<&'a str>,
+    order: &mut Vec<String>,
+) -> Result<(), ConfigError> {
+    match states.get(task_name) {
+        Some(VisitState::Done) => return Ok(()),
+        Some(VisitState::Visiting) => {
+            // Reconstrói o ciclo a partir da primeira ocorrência da task no caminho atual
+            let start = path.iter().position(|t| *t == task_name).unwrap_or(0);
+            let mut cycle: Vec<String> = path[start..].iter().map(|t| t.to_string()).collect();
+            cycle.push(task_name.to_string());
+            return Err(ConfigError::DependencyCycle(cycle));
+        }
+        None => {}
+    }
+
+    states.insert(task_name, VisitState::Visiting);
+    path.push(task_name);
+
+    if let Some(task_config) = config.tasks.get(task_name) {
+        for dependency in &task_config.depends_on {
+            if !config.tasks.contains_key(dependency) {
+                return Err(ConfigError::DependencyNotFound(
+                    task_name.to_string(),
+                    dependency.clone(),
+                ));
+            }
+            visit(config, dependency, states, path, order)?;
+        }
+    }
+
+    path.pop();
+    states.insert(task_name, VisitState::Done);
+    order.push(task_name.to_string());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,563 @@
+use super::*;
+use crate::{config::TaskConfig, subprocess, test_utils::TempDir};
+use std::{
+    fs,
+    path::Path,
+    process::Command,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+fn config_with(tasks: &[(&str, &[&str])]) -> Config {
+    let mut config = Config::default();
+    config.tasks = tasks
+        .iter()
+        .map(|(name, deps)| {
+            (
+                name.to_string(),
+                TaskConfig {
+                    task_type: name.to_string(),
+                    enabled: true,
+                    depends_on: deps.iter().map(|d| d.to_string()).collect(),
+                    ..Default::default()
+                },
+            )
+        })
+        .collect();
+
+    config
+}
+
+#[test]
+fn test_dependencies_run_first() {
+    let config = config_with(&[("deploy", &["build", "test"]), ("build", &[]), ("test", &["build"])]);
+
+    let order = execution_order(&config, &["deploy"]).unwrap();
+
+    assert_eq!(order, vec!["build", "test", "deploy"]);
+}
+
+#[test]
+fn test_shared_dependency_runs_once() {
+    let config = config_with(&[("a", &["shared"]), ("b", &["shared"]), ("shared", &[])]);
+
+    let order = execution_order(&config, &["a", "b", "shared"]).unwrap();
+
+    assert_eq!(order, vec!["shared", "a", "b"]);
+}
+
+#[test]
+fn test_cycle_is_reported() {
+    let config = config_with(&[("a", &["b"]), ("b", &["c"]), ("c", &["a"])]);
+
+    match execution_order(&config, &["a"]) {
+        Err(ConfigError::DependencyCycle(cycle)) => assert_eq!(cycle, vec!["a", "b", "c", "a"]),
+        other => panic!("esperado ciclo, obtido {:?}", other),
+    }
+}
+
+#[test]
+fn test_unknown_dependency() {
+    let config = config_with(&[("a", &["missing"])]);
+
+    assert!(matches!(
+        execution_order(&config, &["a"]),
+        Err(ConfigError::DependencyNotFound(_, _))
+    ));
+}
+
+fn runner_with(config: Config) -> TaskRunner {
+    let mut runner = TaskRunner {
+        registry: TaskRegistry::new(),
+        config,
+        jobs: 1,
+        keep_going: false,
+        timeout: None,
+    };
+    runner.register_tasks();
+    runner
+}
+
+#[test]
+fn test_error_exit_codes() {
+    let runner = runner_with(config_with(&[("a", &["b"]), ("b", &["a"])]));
+
+    let err = runner.run_tasks(&["missing".to_string()]).unwrap_err();
+    assert!(matches!(err, AppError::TaskNotFound { ref task, .. } if task == "missing"));
+    assert_eq!(err.exit_code(), crate::errors::EXIT_TASK_NOT_FOUND);
+
+    let err = runner.run_all().unwrap_err();
+    assert!(matches!(err, AppError::DependencyCycle(_)));
+    assert_eq!(err.exit_code(), crate::errors::EXIT_CONFIG);
+}
+
+/// Configuração com uma task que falha, uma dependente dela e uma independente que
+/// cria `marker` ao executar
+fn failing_config(on_failure: &str, marker: &Path) -> Config {
+    Config::parse(
+        &format!(
+            r#"
+            [fail]
+            type = "exec"
+            command = "false"
+            on_failure = "{}"
+
+            [after-fail]
+            type = "exec"
+            command = "touch"
+            args = ["{}.after"]
+            depends_on = ["fail"]
+
+            [independent]
+            type = "exec"
+            command = "touch"
+            args = ["{}"]
+            "#,
+            on_failure,
+            marker.display(),
+            marker.display()
+        ),
+        "tasks.toml",
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_failure_policies() {
+    let dir = TempDir::new();
+
+    // stop: nada mais é iniciado depois da falha
+    let marker = dir.path().join("stop");
+    let err = runner_with(failing_config("stop", &marker)).run_all().unwrap_err();
+    assert!(matches!(err, AppError::TaskFailed { ref task, .. } if task == "fail"));
+    assert!(!marker.exists());
+
+    // --keep-going: a task independente executa, a dependente não
+    let mut runner = runner_with(failing_config("stop", &marker));
+    runner.set_keep_going(true);
+    assert!(runner.run_all().is_err());
+    assert!(marker.exists());
+    assert!(!marker.with_extension("after").exists());
+
+    // continue: equivalente ao --keep-going para esta task
+    let marker = dir.path().join("continue");
+    assert!(runner_with(failing_config("continue", &marker)).run_all().is_err());
+    assert!(marker.exists());
+    assert!(!marker.with_extension("after").exists());
+
+    // ignore: a falha não impede as dependentes nem afeta o resultado
+    let marker = dir.path().join("ignore");
+    assert!(runner_with(failing_config("ignore", &marker)).run_all().is_ok());
+    assert!(marker.with_extension("after").exists());
+}
+
+#[test]
+fn test_retries() {
+    // Falha na primeira execução (criando o marcador) e passa na segunda
+    let dir = TempDir::new();
+    let marker = dir.path().join("retry");
+    let config = |retries: u32| {
+        Config::parse(
+            &format!(
+                r#"
+                [flaky]
+                type = "exec"
+                command = "sh"
+                args = ["-c", "test -f '{}' || {{ touch '{}'; exit 1; }}"]
+                retries = {}
+                retry_delay = "0s"
+                "#,
+                marker.display(),
+                marker.display(),
+                retries
+            ),
+            "tasks.toml",
+        )
+        .unwrap()
+    };
+
+    assert!(runner_with(config(0)).run_all().is_err());
+    fs::remove_file(&marker).unwrap();
+    assert!(runner_with(config(1)).run_all().is_ok());
+}
+
+/// Task `slow` que executa `script` com `sh -c` e o tempo limite indicado
+fn slow_config(script: &str, timeout: &str) -> Config {
+    Config::parse(
+        &format!(
+            "[slow]\ntype = \"exec\"\ncommand = \"sh\"\nargs = [\"-c\", \"{}\"]\ntimeout = \"{}\"\n",
+            script, timeout
+        ),
+        "tasks.toml",
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_timeout_kills_process_group() {
+    let dir = TempDir::new();
+
+    // O processo neto criaria o marcador depois do tempo limite se não fosse encerrado.
+    // Com `& wait` o `sh` espera o neto; com `& echo` o `sh` termina na hora, mas o neto
+    // mantém os pipes da saída capturada (jobs > 1) abertos além do tempo limite.
+    for (script, jobs) in [("& wait", 1), ("& wait", 2), ("& echo iniciado", 2)] {
+        let marker = dir.path().join(format!("timeout-{}-{}", jobs, script.len()));
+        let config = slow_config(&format!("(sleep 1; touch '{}') {}", marker.display(), script), "100ms");
+        let mut runner = runner_with(config);
+        runner.set_jobs(jobs);
+
+        let start = Instant::now();
+        let err = runner.run_all().unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(1), "{} (jobs = {})", script, jobs);
+        assert!(matches!(err, AppError::TaskFailed { ref source, .. } if matches!(**source, AppError::Timeout { .. })));
+        assert_eq!(err.exit_code(), crate::errors::EXIT_TIMEOUT);
+
+        thread::sleep(Duration::from_millis(1200));
+        assert!(!marker.exists(), "{} (jobs = {})", script, jobs);
+    }
+}
+
+#[test]
+fn test_background_processes_do_not_hold_the_runner() {
+    // O `sh` termina dentro do tempo limite; o processo deixado em segundo plano não
+    // prende o runner até terminar
+    for jobs in [1, 2] {
+        let mut runner = runner_with(slow_config("sleep 4 & echo iniciado", "1s"));
+        runner.set_jobs(jobs);
+
+        let start = Instant::now();
+        assert!(runner.run_all().is_ok());
+        assert!(start.elapsed() < Duration::from_secs(2), "jobs = {}", jobs);
+    }
+}
+
+#[test]
+fn test_global_timeout() {
+    let config = Config::parse("[slow]\ntype = \"exec\"\ncommand = \"sleep\"\nargs = [\"5\"]\n", "tasks.toml").unwrap();
+    let mut runner = runner_with(config);
+    runner.set_timeout(Some(Duration::from_millis(100)));
+
+    let err = runner.run_all().unwrap_err();
+    assert_eq!(err.exit_code(), crate::errors::EXIT_TIMEOUT);
+}
+
+/// Task que sempre falha com um erro recuperável, contando as tentativas em `attempts`
+struct AlwaysFailing {
+    attempts: Arc<AtomicUsize>,
+    retryable: bool,
+}
+
+impl Task for AlwaysFailing {
+    fn name(&self) -> &str {
+        "always-failing"
+    }
+
+    fn description(&self) -> &str {
+        "Falha em todas as tentativas"
+    }
+
+    fn is_retryable(&self, _error: &AppError) -> bool {
+        self.retryable
+    }
+
+    fn run(&self, _params: &crate::params::TaskParams) -> Result<(), AppError> {
+        self.attempts.fetch_add(1, Ordering::SeqCst);
+        Err(AppError::CommandFailed {
+            command: "git push".to_string(),
+            status: Some(128),
+            stderr: String::new(),
+        })
+    }
+}
+
+#[test]
+fn test_task_can_stop_retries() {
+    for (retryable, expected_attempts) in [(true, 3), (false, 1)] {
+        let config = Config::parse(
+            "[failing]\ntype = \"always-failing\"\nretries = 2\nretry_delay = \"0s\"\n",
+            "tasks.toml",
+        )
+        .unwrap();
+        let mut runner = runner_with(config);
+
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = attempts.clone();
+        runner.registry.register("always-failing", move || {
+            Box::new(AlwaysFailing { attempts: counter.clone(), retryable })
+        });
+
+        assert!(runner.run_all().is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), expected_attempts);
+    }
+}
+
+#[test]
+fn test_retryable_errors() {
+    let command_failed = AppError::CommandFailed {
+        command: "git push".to_string(),
+        status: Some(128),
+        stderr: String::new(),
+    };
+    assert!(command_failed.is_retryable());
+
+    // Erros de configuração detectados durante a execução não são tentados de novo
+    assert!(!AppError::Generic("Tag já existe".to_string()).is_retryable());
+    let params = crate::params::TaskParams::new(&[], &HashMap::new()).unwrap();
+    assert!(!AppError::from(params.require_string("tag").unwrap_err()).is_retryable());
+}
+
+/// Configuração com `count` tasks independentes que registram em `log` o início e o
+/// fim da sua execução
+fn parallel_config(log: &str, count: usize) -> Config {
+    let tasks: String = (0..count)
+        .map(|i| {
+            format!(
+                r#"
+                [t{}]
+                type = "exec"
+                command = "sh"
+                args = ["-c", "echo start >> '{}'; sleep 0.2; echo end >> '{}'"]
+                "#,
+                i, log, log
+            )
+        })
+        .collect();
+
+    Config::parse(&tasks, "tasks.toml").unwrap()
+}
+
+/// Maior número de tasks executando ao mesmo tempo segundo o log
+fn max_concurrency(log: &str) -> usize {
+    let (mut running, mut max) = (0, 0);
+    for line in fs::read_to_string(log).unwrap().lines() {
+        if line == "start" {
+            running += 1;
+            max = max.max(running);
+        } else {
+            running -= 1;
+        }
+    }
+    max
+}
+
+#[test]
+fn test_parallel_jobs_are_bounded() {
+    for (jobs, expected) in [(1, 1), (2, 2), (8, 4)] {
+        let dir = TempDir::new();
+        let log = dir.join("log");
+
+        let mut runner = runner_with(parallel_config(&log, 4));
+        runner.set_jobs(jobs);
+        runner.run_all().unwrap();
+
+        assert_eq!(max_concurrency(&log), expected, "jobs = {}", jobs);
+    }
+}
+
+#[test]
+fn test_parallel_respects_dependencies() {
+    let dir = TempDir::new();
+    let log = dir.join("log");
+    let config = Config::parse(
+        &format!(
+            r#"
+            [slow]
+            type = "exec"
+            command = "sh"
+            args = ["-c", "sleep 0.2; echo slow >> '{log}'"]
+
+            [after-slow]
+            type = "exec"
+            command = "sh"
+            args = ["-c", "echo after-slow >> '{log}'"]
+            depends_on = ["slow"]
+
+            [independent]
+            type = "exec"
+            command = "sh"
+            args = ["-c", "echo independent >> '{log}'"]
+            "#,
+            log = log
+        ),
+        "tasks.toml",
+    )
+    .unwrap();
+
+    let mut runner = runner_with(config);
+    runner.set_jobs(3);
+    runner.run_all().unwrap();
+
+    assert_eq!(fs::read_to_string(&log).unwrap(), "independent\nslow\nafter-slow\n");
+}
+
+#[test]
+fn test_output_is_buffered_per_task() {
+    let task = |name: &'static str| {
+        move || {
+            output::capture(|| {
+                for i in 0..20 {
+                    task_println!("{} {}", name, i);
+                    subprocess::stream(Command::new("echo").arg(name), None).unwrap();
+                }
+            })
+            .1
+        }
+    };
+
+    let (first, second) = thread::scope(|scope| {
+        let first = scope.spawn(task("a"));
+        let second = scope.spawn(task("b"));
+        (first.join().unwrap(), second.join().unwrap())
+    });
+
+    // Cada task recebe apenas as suas linhas, inclusive as dos processos filhos
+    for (lines, name) in [(first, "a"), (second, "b")] {
+        assert_eq!(lines.len(), 40);
+        assert!(lines.iter().all(|(_, line)| line.starts_with(name)));
+    }
+}
+
+#[test]
+fn test_summary_follows_execution_order() {
+    let order: Vec<String> = ["build", "test", "deploy", "docs", "lint", "release"]
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    let outcomes = HashMap::from([
+        (0, TaskOutcome::Success(Duration::from_millis(10))),
+        (1, TaskOutcome::Failed(Duration::from_millis(20), AppError::Generic("falhou".to_string()))),
+        (2, TaskOutcome::Blocked("test".to_string())),
+        (3, TaskOutcome::Skipped),
+        (4, TaskOutcome::Ignored(Duration::from_millis(30))),
+    ]);
+
+    let summary = TaskRunner::summary(&order, &outcomes);
+
+    assert_eq!(
+        summary.lines().collect::<Vec<_>>(),
+        vec![
+            "",
+            "📊 Resumo:",
+            "  ✅ build (0.01s)",
+            "  ❌ test (0.02s)",
+            "  ⏭️  deploy (dependência 'test' falhou)",
+            "  ⏭️  docs (desabilitada)",
+            "  ⚠️  lint (0.03s, falha ignorada)",
+            "  ⏭️  release (não executada)",
+            "",
+            "  Total: 1 sucesso(s), 1 falha(s), 3 pulada(s), 1 falha(s) ignorada(s)",
+        ]
+    );
+}
+
+#[test]
+fn test_disabled_tasks() {
+    let dir = TempDir::new();
+    let (setup, main) = (dir.join("setup"), dir.join("main"));
+    let config = || {
+        Config::parse(
+            &format!(
+                r#"
+                [setup]
+                type = "exec"
+                command = "touch"
+                args = ["{}"]
+                enabled = false
+
+                [main]
+                type = "exec"
+                command = "touch"
+                args = ["{}"]
+                depends_on = ["setup"]
+                "#,
+                setup, main
+            ),
+            "tasks.toml",
+        )
+        .unwrap()
+    };
+
+    // A task desabilitada é pulada, mas não impede as que dependem dela
+    runner_with(config()).run_all().unwrap();
+    assert!(!Path::new(&setup).exists());
+    assert!(Path::new(&main).exists());
+
+    // --enable / --disable sobrescrevem a configuração
+    fs::remove_file(&main).unwrap();
+    let mut runner = runner_with(config());
+    runner.set_enabled("setup", true).unwrap();
+    runner.set_enabled("main", false).unwrap();
+    runner.run_all().unwrap();
+    assert!(Path::new(&setup).exists());
+    assert!(!Path::new(&main).exists());
+
+    match runner.set_enabled("missing", true) {
+        Err(AppError::TaskNotFound { task, available }) => {
+            assert_eq!(task, "missing");
+            assert_eq!(available, "main, setup");
+        }
+        other => panic!("esperado TaskNotFound, obtido {:?}", other),
+    }
+}
+
+#[test]
+fn test_validate_reports_all_problems() {
+    let config = Config::parse(
+        r#"
+        [deploy]
+        type = "desconhecido"
+
+        [build]
+        release = "sim"
+        depends_on = ["missing"]
+
+        [a]
+        type = "exec"
+        command = "true"
+        depends_on = ["b"]
+
+        [b]
+        type = "exec"
+        command = "true"
+        depends_on = ["a"]
+        enabled = false
+        "#,
+        "tasks.toml",
+    )
+    .unwrap();
+
+    let diagnostics = match runner_with(config).validate() {
+        Err(AppError::Validation(diagnostics)) => diagnostics.0,
+        other => panic!("esperado Validation, obtido {:?}", other),
+    };
+    let mut problems: Vec<(&str, &str)> = diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.task.as_str(), diagnostic.message.as_str()))
+        .collect();
+    problems.sort();
+
+    assert_eq!(problems.len(), 4, "{:?}", problems);
+    assert!(problems[0].0 == "a" && problems[0].1.contains("a -> b -> a"));
+    assert!(problems[1].0 == "build" && problems[1].1.contains("release"));
+    assert!(problems[2].0 == "build" && problems[2].1.contains("missing"));
+    assert!(problems[3].0 == "deploy" && problems[3].1.contains("desconhecido"));
+
+    // Todas as posições apontam para o arquivo de configuração
+    assert!(diagnostics.iter().all(|diagnostic| diagnostic.location.is_some()));
+}
+
+#[test]
+fn test_validate_and_describe() {
+    let runner = runner_with(Config::parse("[release]\ntype = \"git-tag\"\ntag = \"v1\"\n", "tasks.toml").unwrap());
+
+    runner.validate().unwrap();
+    runner.list_configured_tasks();
+    runner.describe_task("release").unwrap();
+    runner.describe_task("exec").unwrap();
+    assert!(matches!(
+        runner.describe_task("missing"),
+        Err(AppError::TaskNotFound { ref task, .. }) if task == "missing"
+    ));
+}
@@ -0,0 +1,417 @@
+//! Execução de processos externos usados pelas tasks (git, cargo, ...)
+//!
+//! O runner pode definir um tempo limite para a task da thread atual (`with_timeout`).
+//! Os processos iniciados enquanto ele vale ganham um grupo de processos próprio, que
+//! é encerrado por inteiro quando o tempo se esgota, para que processos criados pelo
+//! comando (ex: `sh -c "sleep 600"`) não fiquem rodando.
+//!
+//! Por estarem fora do grupo do runner, esses processos não recebem os sinais
+//! enviados a ele (Ctrl+C ou `kill` no grupo do runner). Enquanto eles rodam, o
+//! runner repassa SIGINT e SIGTERM para os seus grupos antes de terminar.
+
+use crate::errors::AppError;
+use crate::output::{self, Stream};
+use std::{
+    cell::Cell,
+    io::{BufRead, BufReader, Read, Write},
+    process::{Child, Command, ExitStatus, Output, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Intervalo entre as verificações de um processo em execução
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Por quanto tempo a saída ainda é lida depois que o processo termina. Processos
+/// iniciados por ele em segundo plano (ex: `sh -c "./server &"`) herdam os pipes e
+/// podem mantê-los abertos indefinidamente, então a leitura não espera que eles fechem.
+const DRAIN_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Momento em que os processos da task atual devem ser encerrados
+#[derive(Debug, Clone, Copy)]
+struct Deadline {
+    at: Instant,
+    timeout: Duration,
+}
+
+thread_local! {
+    static DEADLINE: Cell<Option<Deadline>> = const { Cell::new(None) };
+}
+
+/// Executa `f` com o tempo limite `timeout` valendo para os processos que ela iniciar
+pub fn with_timeout<T>(timeout: Option<Duration>, f: impl FnOnce() -> T) -> T {
+    let deadline = timeout.and_then(|timeout| {
+        let at = Instant::now().checked_add(timeout)?;
+        Some(Deadline { at, timeout })
+    });
+
+    let previous = DEADLINE.replace(deadline);
+    let result = f();
+    DEADLINE.set(previous);
+    result
+}
+
+/// Representação legível de um comando, usada nas mensagens de erro
+pub fn describe(cmd: &Command) -> String {
+    std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|part| part.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Executa o comando capturando a saída, sem verificar o código de saída
+pub fn output(cmd: &mut Command) -> Result<Output, AppError> {
+    let command = describe(cmd);
+    let mut process = spawn(
+        cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()),
+        &command,
+    )?;
+
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    let status = collect(&mut process, &command, |stream, chunk| match stream {
+        Stream::Stdout => stdout.extend(chunk),
+        Stream::Stderr => stderr.extend(chunk),
+    })?;
+
+    Ok(Output { status, stdout, stderr })
+}
+
+/// Executa o comando e retorna o stdout, falhando com o stderr do processo se ele não terminar com sucesso
+pub fn run(cmd: &mut Command) -> Result<String, AppError> {
+    let output = output(cmd)?;
+
+    if !output.status.success() {
+        return Err(AppError::CommandFailed {
+            command: describe(cmd),
+            status: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Executa o comando repassando stdout/stderr linha a linha para a saída da task. Se a
+/// saída não estiver sendo capturada, o processo usa diretamente o terminal do runner.
+///
+/// Se `input` for informado, ele é escrito no stdin do processo. Retorna o status de
+/// saída sem verificá-lo; cabe a quem chama decidir o que é sucesso.
+pub fn stream(cmd: &mut Command, input: Option<&str>) -> Result<ExitStatus, AppError> {
+    let command = describe(cmd);
+
+    if input.is_some() {
+        cmd.stdin(Stdio::piped());
+    }
+
+    // Sem captura de saída (tasks em sequência) o processo escreve direto no terminal
+    let sink = output::sink();
+    if !sink.is_captured() {
+        let mut process = spawn(cmd.stdout(Stdio::inherit()).stderr(Stdio::inherit()), &command)?;
+        write_input(&mut process.child, input);
+        return wait(&mut process, &command);
+    }
+
+    let mut process = spawn(cmd.stdout(Stdio::piped()).stderr(Stdio::piped()), &command)?;
+    write_input(&mut process.child, input);
+
+    collect(&mut process, &command, |stream, line| {
+        let text = String::from_utf8_lossy(&line);
+        sink.print(stream, text.trim_end_matches(['\n', '\r']));
+    })
+}
+
+/// Processo iniciado por `spawn`
+struct Process {
+    child: Child,
+    deadline: Option<Deadline>,
+    /// Mantém o grupo do processo registrado para receber os sinais do runner até que
+    /// o processo termine
+    #[cfg(unix)]
+    _group: Option<signals::ActiveGroup>,
+}
+
+/// Inicia o processo, em um grupo de processos próprio se houver tempo limite.
+///
+/// Sem tempo limite o processo continua no grupo do runner e recebe normalmente os
+/// sinais do terminal (ex: Ctrl+C).
+fn spawn(cmd: &mut Command, command: &str) -> Result<Process, AppError> {
+    let deadline = DEADLINE.get();
+
+    #[cfg(unix)]
+    if deadline.is_some() {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
+    let child = cmd.spawn().map_err(|source| io_error(command, source))?;
+    Ok(Process {
+        #[cfg(unix)]
+        _group: deadline.and_then(|_| signals::ActiveGroup::register(&child)),
+        child,
+        deadline,
+    })
+}
+
+/// Aguarda o processo terminar, encerrando o seu grupo se o tempo limite se esgotar
+fn wait(process: &mut Process, command: &str) -> Result<ExitStatus, AppError> {
+    let child = &mut process.child;
+    let Some(deadline) = process.deadline else {
+        return child.wait().map_err(|source| io_error(command, source));
+    };
+
+    loop {
+        if let Some(status) = child.try_wait().map_err(|source| io_error(command, source))? {
+            return Ok(status);
+        }
+
+        let now = Instant::now();
+        if now >= deadline.at {
+            return Err(timeout(child, command, deadline));
+        }
+
+        thread::sleep((deadline.at - now).min(POLL_INTERVAL));
+    }
+}
+
+/// Repassa a saída do processo para `forward`, linha a linha, até que ele termine.
+///
+/// Depois que o processo termina, a saída continua sendo lida até os pipes fecharem
+/// ou por no máximo `DRAIN_TIMEOUT`, para que processos deixados em segundo plano não
+/// prendam o runner.
+fn collect(
+    process: &mut Process,
+    command: &str,
+    mut forward: impl FnMut(Stream, Vec<u8>),
+) -> Result<ExitStatus, AppError> {
+    let lines = read_pipes(&mut process.child);
+    let mut exited: Option<(ExitStatus, Instant)> = None;
+
+    loop {
+        let now = Instant::now();
+        let mut next_check = POLL_INTERVAL;
+
+        // O tempo limite vale também enquanto a saída é lida depois que o processo termina,
+        // já que processos deixados em segundo plano continuam no seu grupo
+        if let Some(deadline) = process.deadline {
+            if now >= deadline.at {
+                return Err(timeout(&mut process.child, command, deadline));
+            }
+            next_check = next_check.min(deadline.at - now);
+        }
+
+        match exited {
+            Some((status, drain_until)) => {
+                if now >= drain_until {
+                    return Ok(status);
+                }
+                next_check = next_check.min(drain_until - now);
+            }
+            None => {
+                if let Some(status) = process.child.try_wait().map_err(|source| io_error(command, source))? {
+                    exited = Some((status, now + DRAIN_TIMEOUT));
+                    continue;
+                }
+            }
+        }
+
+        match lines.recv_timeout(next_check) {
+            Ok((stream, line)) => forward(stream, line),
+            Err(RecvTimeoutError::Timeout) => {}
+            // Os pipes foram fechados: falta apenas o processo terminar
+            Err(RecvTimeoutError::Disconnected) => {
+                return match exited {
+                    Some((status, _)) => Ok(status),
+                    None => wait(process, command),
+                };
+            }
+        }
+    }
+}
+
+/// Encerra o grupo do processo que excedeu o tempo limite. O grupo continua existindo
+/// enquanto houver processos nele, mesmo que o processo principal já tenha terminado.
+fn timeout(child: &mut Child, command: &str, deadline: Deadline) -> AppError {
+    kill_group(child);
+    let _ = child.wait();
+
+    AppError::Timeout {
+        command: command.to_string(),
+        timeout: deadline.timeout,
+    }
+}
+
+/// Encerra o processo e todos os processos do seu grupo
+#[cfg(unix)]
+fn kill_group(child: &mut Child) {
+    // O processo é o líder do grupo, então o id do grupo é o seu próprio pid
+    let killed = match libc::pid_t::try_from(child.id()) {
+        Ok(pgid) => signals::send_to_group(pgid, libc::SIGKILL),
+        Err(_) => false,
+    };
+
+    if !killed {
+        let _ = child.kill();
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_group(child: &mut Child) {
+    let _ = child.kill();
+}
+
+fn io_error(command: &str, source: std::io::Error) -> AppError {
+    AppError::Io {
+        context: format!("Falha ao executar '{}'", command),
+        source,
+    }
+}
+
+/// Escreve `input` no stdin do processo. O stdin é fechado ao final da escrita para que o
+/// processo receba EOF.
+fn write_input(child: &mut Child, input: Option<&str>) {
+    if let (Some(mut stdin), Some(input)) = (child.stdin.take(), input) {
+        let input = input.to_string();
+        thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+    }
+}
+
+/// Lê stdout e stderr do processo em threads próprias, que enviam cada linha lida.
+///
+/// As threads não são aguardadas: quando `collect` para de receber, elas continuam
+/// lendo e descartando a saída até os pipes fecharem, para que os processos que ainda
+/// escrevem neles não recebam SIGPIPE.
+fn read_pipes(child: &mut Child) -> Receiver<(Stream, Vec<u8>)> {
+    let (sender, receiver) = mpsc::channel();
+
+    if let Some(stdout) = child.stdout.take() {
+        let sender = sender.clone();
+        thread::spawn(move || read_lines(stdout, Stream::Stdout, sender));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        thread::spawn(move || read_lines(stderr, Stream::Stderr, sender));
+    }
+
+    receiver
+}
+
+/// Envia cada linha lida de `reader` (incluindo a quebra de linha) enquanto houver quem receba
+fn read_lines(reader: impl Read, stream: Stream, sender: Sender<(Stream, Vec<u8>)>) {
+    let mut reader = BufReader::new(reader);
+    let mut receiving = true;
+
+    loop {
+        let mut line = Vec::new();
+        if !matches!(reader.read_until(b'\n', &mut line), Ok(n) if n > 0) {
+            break;
+        }
+        if receiving {
+            receiving = sender.send((stream, line)).is_ok();
+        }
+    }
+}
+
+/// Repasse de SIGINT/SIGTERM para os grupos de processos criados pelo runner.
+///
+/// O tratador de sinais só lê atômicos e chama `kill` e `raise`, que podem ser usados
+/// com segurança dentro de um tratador.
+#[cfg(unix)]
+mod signals {
+    use std::process::Child;
+    use std::sync::{
+        Once,
+        atomic::{AtomicI32, Ordering},
+    };
+
+    /// Quantidade máxima de grupos acompanhados ao mesmo tempo
+    const MAX_GROUPS: usize = 256;
+
+    /// Ids dos grupos de processos em execução (0 indica uma posição livre)
+    static GROUPS: [AtomicI32; MAX_GROUPS] = [const { AtomicI32::new(0) }; MAX_GROUPS];
+
+    /// Envia `signal` para todos os processos do grupo `pgid`, retornando se o envio funcionou
+    pub fn send_to_group(pgid: libc::pid_t, signal: libc::c_int) -> bool {
+        unsafe { libc::kill(-pgid, signal) == 0 }
+    }
+
+    /// Grupo de processos registrado para receber os sinais do runner; o registro é
+    /// removido quando o valor é descartado
+    pub struct ActiveGroup(usize);
+
+    impl ActiveGroup {
+        /// Registra o grupo do processo, que deve ser o líder do grupo. Retorna `None` se
+        /// todas as posições estiverem ocupadas.
+        pub fn register(child: &Child) -> Option<Self> {
+            install_handlers();
+
+            let pgid = libc::pid_t::try_from(child.id()).ok()?;
+            GROUPS
+                .iter()
+                .position(|slot| slot.compare_exchange(0, pgid, Ordering::SeqCst, Ordering::SeqCst).is_ok())
+                .map(ActiveGroup)
+        }
+    }
+
+    impl Drop for ActiveGroup {
+        fn drop(&mut self) {
+            GROUPS[self.0].store(0, Ordering::SeqCst);
+        }
+    }
+
+    /// Grupos registrados no momento
+    #[cfg(test)]
+    pub fn active_groups() -> Vec<libc::pid_t> {
+        GROUPS
+            .iter()
+            .map(|slot| slot.load(Ordering::SeqCst))
+            .filter(|pgid| *pgid != 0)
+            .collect()
+    }
+
+    fn install_handlers() {
+        static INSTALL: Once = Once::new();
+
+        INSTALL.call_once(|| {
+            for signal in [libc::SIGINT, libc::SIGTERM] {
+                unsafe {
+                    // Um sinal ignorado pelo runner (ex: tasks em segundo plano) continua ignorado
+                    let mut previous: libc::sigaction = std::mem::zeroed();
+                    if libc::sigaction(signal, std::ptr::null(), &mut previous) != 0
+                        || previous.sa_sigaction == libc::SIG_IGN
+                    {
+                        continue;
+                    }
+
+                    // SA_RESETHAND restaura o tratamento padrão ao receber o sinal
+                    let mut action: libc::sigaction = std::mem::zeroed();
+                    action.sa_sigaction = forward as extern "C" fn(libc::c_int) as libc::sighandler_t;
+                    action.sa_flags = libc::SA_RESTART | libc::SA_RESETHAND;
+                    libc::sigemptyset(&mut action.sa_mask);
+                    libc::sigaction(signal, &action, std::ptr::null_mut());
+                }
+            }
+        });
+    }
+
+    /// Repassa o sinal para os grupos em execução e o envia novamente ao runner, já com
+    /// o tratamento padrão, para que ele termine como terminaria sem o tratador
+    extern "C" fn forward(signal: libc::c_int) {
+        for slot in &GROUPS {
+            let pgid = slot.load(Ordering::SeqCst);
+            if pgid != 0 {
+                send_to_group(pgid, signal);
+            }
+        }
+
+        unsafe {
+            libc::raise(signal);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
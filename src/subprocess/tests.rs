@@ -0,0 +1,30 @@
+use super::*;
+
+#[cfg(unix)]
+#[test]
+fn test_groups_are_registered_while_running() {
+    let mut process = with_timeout(Some(Duration::from_secs(10)), || {
+        spawn(Command::new("sleep").arg("10"), "sleep 10")
+    })
+    .unwrap();
+    let pgid = process.child.id() as libc::pid_t;
+
+    assert!(signals::active_groups().contains(&pgid));
+
+    // O processo está no próprio grupo, que é o destino dos sinais repassados pelo runner
+    assert!(signals::send_to_group(pgid, libc::SIGTERM));
+    let status = wait(&mut process, "sleep 10").unwrap();
+    assert!(!status.success());
+
+    drop(process);
+    assert!(!signals::active_groups().contains(&pgid));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_processes_without_timeout_are_not_registered() {
+    let mut process = spawn(&mut Command::new("true"), "true").unwrap();
+
+    assert!(process._group.is_none());
+    assert!(wait(&mut process, "true").unwrap().success());
+}
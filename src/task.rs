@@ -1,4 +1,4 @@
-use crate::{config::TaskConfig, errors::AppError, params::{ParamDefinition, TaskParams}};
+use crate::{config::TaskConfig, errors::AppError, params::{ParamDefinition, ParamErrors, TaskParams}};
 
 /// Trait que todas as tasks devem implementar
 pub trait Task: Send + Sync {
@@ -13,11 +13,33 @@ pub trait Task: Send + Sync {
         vec![]
     }
     
+    /// Se parâmetros sem definição devem ser rejeitados.
+    /// Tasks que aceitam parâmetros livres devem retornar `false`.
+    fn strict_params(&self) -> bool {
+        true
+    }
+    
+    /// Valida e resolve os parâmetros da task a partir da configuração
+    fn resolve_params(&self, config: &TaskConfig) -> Result<TaskParams, ParamErrors> {
+        let definitions = self.param_definitions();
+
+        if self.strict_params() {
+            TaskParams::new(&definitions, &config.params)
+        } else {
+            TaskParams::permissive(&definitions, &config.params)
+        }
+    }
+    
+    /// Se a task deve ser executada novamente após `error` (quando `retries` está
+    /// configurado). Tasks podem sobrescrever para marcar erros como permanentes.
+    fn is_retryable(&self, error: &AppError) -> bool {
+        error.is_retryable()
+    }
+    
     /// Executa a task com a configuração fornecida
     fn execute(&self, config: &TaskConfig) -> Result<(), AppError> {
-        // Valida e prepara os parâmetros
-        let params = TaskParams::new(self.param_definitions(), config.params.clone());
-        params.validate()?;
+        // Valida e resolve os parâmetros
+        let params = self.resolve_params(config)?;
 
         // Chama a execução com os parâmetros validados
         self.run(&params)
@@ -1,10 +1,8 @@
-// Exemplo de implementação de uma task de build
-// Para usar, descomente este código e siga as instruções no README.md
-
-/*
-use crate::config::TaskConfig;
+use crate::errors::AppError;
+use crate::output::task_println;
+use crate::params::{ParamDefinition, ParamType, TaskParams};
+use crate::subprocess;
 use crate::task::Task;
-use anyhow::{Context, Result};
 use std::process::Command;
 
 pub struct BuildTask;
@@ -14,12 +12,45 @@ impl BuildTask {
         Self
     }
 
-    fn get_param_bool(config: &TaskConfig, key: &str, default: bool) -> bool {
-        config
-            .params
-            .get(key)
-            .and_then(|v| v.as_bool())
-            .unwrap_or(default)
+    /// Lê um parâmetro do tipo Array de strings (validado em `items`)
+    fn get_string_list(params: &TaskParams, key: &str) -> Vec<String> {
+        params
+            .get_array(key)
+            .map(|values| values.iter().filter_map(|value| value.as_str()).map(|s| s.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Monta o `cargo build` com as opções configuradas
+    fn build_command(params: &TaskParams) -> Command {
+        let mut cmd = Command::new("cargo");
+        cmd.arg("build");
+
+        if params.get_bool_or("release", false) {
+            cmd.arg("--release");
+        }
+
+        let features = Self::get_string_list(params, "features");
+        if !features.is_empty() {
+            cmd.arg("--features").arg(features.join(","));
+        }
+
+        if params.get_bool_or("all_features", false) {
+            cmd.arg("--all-features");
+        }
+
+        if let Some(target) = params.get_string("target") {
+            cmd.arg("--target").arg(target);
+        }
+
+        if let Some(package) = params.get_string("package") {
+            cmd.arg("--package").arg(package);
+        }
+
+        if let Some(manifest_path) = params.get_string("manifest_path") {
+            cmd.arg("--manifest-path").arg(manifest_path);
+        }
+
+        cmd
     }
 }
 
@@ -29,46 +60,63 @@ impl Task for BuildTask {
     }
 
     fn description(&self) -> &str {
-        "Compila o projeto Rust"
+        "Compila o projeto Rust com cargo build"
     }
 
-    fn execute(&self, config: &TaskConfig) -> Result<()> {
-        let release = Self::get_param_bool(config, "release", false);
-
-        println!("🔨 Compilando o projeto...");
+    fn param_definitions(&self) -> Vec<ParamDefinition> {
+        vec![
+            ParamDefinition::new("release", ParamType::Bool)
+                .default_bool(false)
+                .description("Compila em modo release (otimizado)"),
+
+            ParamDefinition::new("features", ParamType::Array)
+                .optional()
+                .items(ParamType::String)
+                .description("Lista de features a habilitar"),
+
+            ParamDefinition::new("all_features", ParamType::Bool)
+                .default_bool(false)
+                .description("Habilita todas as features"),
+
+            ParamDefinition::new("target", ParamType::String)
+                .optional()
+                .description("Target triple de destino (ex: x86_64-unknown-linux-musl)"),
+
+            ParamDefinition::new("package", ParamType::String)
+                .optional()
+                .description("Pacote do workspace a ser compilado"),
+
+            ParamDefinition::new("manifest_path", ParamType::String)
+                .optional()
+                .description("Caminho do Cargo.toml (default: o do diretório atual)"),
+        ]
+    }
 
-        let mut cmd = Command::new("cargo");
-        cmd.arg("build");
+    fn run(&self, params: &TaskParams) -> Result<(), AppError> {
+        task_println!("🔨 Compilando o projeto...");
 
-        if release {
-            cmd.arg("--release");
-            println!("📦 Modo: Release (otimizado)");
+        if params.get_bool_or("release", false) {
+            task_println!("📦 Modo: Release (otimizado)");
         } else {
-            println!("🐛 Modo: Debug");
+            task_println!("🐛 Modo: Debug");
         }
 
-        let output = cmd
-            .output()
-            .context("Falha ao executar cargo build")?;
+        let mut cmd = Self::build_command(params);
+        let status = subprocess::stream(&mut cmd, None)?;
 
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Erro na compilação:\n{}", error);
+        if !status.success() {
+            return Err(AppError::CommandFailed {
+                command: subprocess::describe(&cmd),
+                status: status.code(),
+                stderr: String::new(),
+            });
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        println!("{}", stdout);
-
-        println!("✅ Compilação concluída com sucesso!");
+        task_println!("✅ Compilação concluída com sucesso!");
 
         Ok(())
     }
 }
-*/
-
-// Para habilitar esta task:
-// 1. Descomente o código acima
-// 2. Adicione em src/tasks/mod.rs: pub mod build;
-// 3. Registre em src/runner.rs no método register_tasks():
-//    self.registry.register("build", || Box::new(crate::tasks::build::BuildTask::new()));
-// 4. Habilite a task em tasks.toml mudando enabled = true
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,54 @@
+use super::*;
+use crate::config::Config;
+
+/// Argumentos do `cargo` montados para os parâmetros informados (em TOML)
+fn cargo_args(params: &str) -> Vec<String> {
+    let config = Config::parse(&format!("[build]\n{}", params), "tasks.toml").unwrap();
+    let task_config = config.find_task("build").unwrap();
+    let params = BuildTask::new().resolve_params(task_config).unwrap();
+
+    let cmd = BuildTask::build_command(&params);
+    assert_eq!(cmd.get_program(), "cargo");
+    cmd.get_args().map(|arg| arg.to_string_lossy().to_string()).collect()
+}
+
+#[test]
+fn test_default_build() {
+    assert_eq!(cargo_args(""), ["build"]);
+}
+
+#[test]
+fn test_build_options() {
+    let args = cargo_args(
+        r#"
+        release = true
+        features = ["cli", "tls"]
+        all_features = true
+        target = "x86_64-unknown-linux-musl"
+        package = "core"
+        manifest_path = "crates/core/Cargo.toml"
+        "#,
+    );
+
+    assert_eq!(
+        args,
+        [
+            "build",
+            "--release",
+            "--features",
+            "cli,tls",
+            "--all-features",
+            "--target",
+            "x86_64-unknown-linux-musl",
+            "--package",
+            "core",
+            "--manifest-path",
+            "crates/core/Cargo.toml",
+        ]
+    );
+}
+
+#[test]
+fn test_empty_features_are_omitted() {
+    assert_eq!(cargo_args("features = []\n"), ["build"]);
+}
@@ -0,0 +1,153 @@
+use crate::errors::AppError;
+use crate::output::task_println;
+use crate::params::{ParamDefinition, ParamType, TaskParams};
+use crate::subprocess;
+use crate::task::Task;
+use std::process::Command;
+use toml::Value;
+
+pub struct ExecTask;
+
+impl ExecTask {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Converte um valor escalar do TOML (já validado em `items`) para o texto usado
+    /// como argumento ou variável de ambiente
+    fn value_to_string(value: &Value) -> String {
+        match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    /// Monta o comando, executando-o via shell se solicitado.
+    ///
+    /// Com `shell`, apenas `command` é interpretado pelo shell; os `args` são
+    /// repassados como parâmetros posicionais (`"$@"`), sem expansão nem divisão
+    /// por espaços.
+    fn build_command(command: &str, args: &[String], shell: bool) -> Command {
+        if !shell {
+            let mut cmd = Command::new(command);
+            cmd.args(args);
+            return cmd;
+        }
+
+        if cfg!(windows) {
+            // O cmd não tem parâmetros posicionais: cada argumento vai entre aspas
+            let script = std::iter::once(command.to_string())
+                .chain(args.iter().map(|arg| format!("\"{}\"", arg.replace('"', "\"\""))))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let mut cmd = Command::new("cmd");
+            cmd.arg("/C").arg(script);
+            cmd
+        } else {
+            let script = if args.is_empty() {
+                command.to_string()
+            } else {
+                format!("{} \"$@\"", command)
+            };
+
+            // O primeiro argumento após o script vira o `$0`
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c").arg(script).arg("sh").args(args);
+            cmd
+        }
+    }
+}
+
+impl Task for ExecTask {
+    fn name(&self) -> &str {
+        "exec"
+    }
+
+    fn description(&self) -> &str {
+        "Executa um comando arbitrário"
+    }
+
+    fn param_definitions(&self) -> Vec<ParamDefinition> {
+        vec![
+            ParamDefinition::new("command", ParamType::String)
+                .required()
+                .description("Comando a ser executado"),
+
+            ParamDefinition::new("args", ParamType::Array)
+                .optional()
+                .items(ParamType::Scalar)
+                .description("Argumentos passados ao comando"),
+
+            ParamDefinition::new("cwd", ParamType::String)
+                .optional()
+                .description("Diretório de trabalho do comando"),
+
+            ParamDefinition::new("env", ParamType::Table)
+                .optional()
+                .items(ParamType::Scalar)
+                .description("Variáveis de ambiente adicionais"),
+
+            ParamDefinition::new("shell", ParamType::Bool)
+                .default_bool(false)
+                .description("Executa o comando através do shell (sh -c / cmd /C)"),
+
+            ParamDefinition::new("exit_codes", ParamType::Array)
+                .optional()
+                .items(ParamType::Integer)
+                .description("Códigos de saída considerados sucesso (default: [0])"),
+
+            ParamDefinition::new("stdin", ParamType::String)
+                .optional()
+                .description("Texto enviado para a entrada padrão do comando"),
+        ]
+    }
+
+    fn run(&self, params: &TaskParams) -> Result<(), AppError> {
+        let command = params.require_string("command")?;
+
+        let args: Vec<String> = params
+            .get_array("args")
+            .map(|values| values.iter().map(Self::value_to_string).collect())
+            .unwrap_or_default();
+
+        let exit_codes: Vec<i64> = match params.get_array("exit_codes") {
+            Some(values) => values.iter().filter_map(|value| value.as_integer()).collect(),
+            None => vec![0],
+        };
+
+        let mut cmd = Self::build_command(&command, &args, params.get_bool_or("shell", false));
+
+        if let Some(cwd) = params.get_string("cwd") {
+            cmd.current_dir(cwd);
+        }
+
+        if let Some(env) = params.get_table("env") {
+            for (key, value) in env {
+                cmd.env(key, Self::value_to_string(value));
+            }
+        }
+
+        task_println!("▶️  {}", subprocess::describe(&cmd));
+
+        let stdin = params.get_string("stdin");
+        let status = subprocess::stream(&mut cmd, stdin.as_deref())?;
+
+        let success = status
+            .code()
+            .is_some_and(|code| exit_codes.contains(&(code as i64)));
+
+        if !success {
+            return Err(AppError::CommandFailed {
+                command: subprocess::describe(&cmd),
+                status: status.code(),
+                stderr: String::new(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,113 @@
+use super::*;
+use crate::{config::Config, output, params::ParamErrorKind, test_utils::TempDir};
+use std::time::{Duration, Instant};
+
+/// Executa uma task `exec` com os parâmetros informados (em TOML), retornando o
+/// resultado e as linhas de saída capturadas
+fn run_exec(params: &str) -> (Result<(), AppError>, Vec<String>) {
+    let config = Config::parse(&format!("[exec]\n{}", params), "tasks.toml").unwrap();
+    let task_config = config.find_task("exec").unwrap();
+
+    let (result, lines) = output::capture(|| ExecTask::new().execute(task_config));
+    (result, lines.into_iter().map(|(_, line)| line).collect())
+}
+
+#[test]
+fn test_exit_codes() {
+    let (result, _) = run_exec("command = \"sh\"\nargs = [\"-c\", \"exit 3\"]\n");
+    match result {
+        Err(AppError::CommandFailed { command, status, .. }) => {
+            assert_eq!(command, "sh -c exit 3");
+            assert_eq!(status, Some(3));
+        }
+        other => panic!("esperado CommandFailed, obtido {:?}", other),
+    }
+
+    let (result, _) = run_exec("command = \"sh\"\nargs = [\"-c\", \"exit 3\"]\nexit_codes = [0, 3]\n");
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_stdin_and_env() {
+    let (result, lines) = run_exec("command = \"cat\"\nstdin = \"linha 1\\nlinha 2\\n\"\n");
+    assert!(result.is_ok());
+    assert!(lines.ends_with(&["linha 1".to_string(), "linha 2".to_string()]));
+
+    let (result, lines) = run_exec(
+        "command = \"sh\"\nargs = [\"-c\", \"echo $NOME-$VERSAO\"]\nenv = { NOME = \"runner\", VERSAO = 2 }\n",
+    );
+    assert!(result.is_ok());
+    assert_eq!(lines.last().map(|line| line.as_str()), Some("runner-2"));
+}
+
+#[test]
+fn test_background_processes_do_not_block() {
+    let params = "command = \"sh\"\nargs = [\"-c\", \"sleep 3 & echo iniciado\"]\n";
+
+    // Saída capturada (tasks em paralelo): a leitura para pouco depois do fim do `sh`
+    let start = Instant::now();
+    let (result, lines) = run_exec(params);
+    assert!(result.is_ok());
+    assert!(lines.contains(&"iniciado".to_string()));
+    assert!(start.elapsed() < Duration::from_secs(2));
+
+    // Sem captura o processo usa o terminal do runner e não há pipes a esperar
+    let config = Config::parse(&format!("[exec]\n{}", params), "tasks.toml").unwrap();
+    let start = Instant::now();
+    assert!(ExecTask::new().execute(config.find_task("exec").unwrap()).is_ok());
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn test_cwd() {
+    let dir = TempDir::new();
+    dir.write("marcador.txt", "");
+
+    let (result, lines) = run_exec(&format!("command = \"ls\"\ncwd = '{}'\n", dir.path().display()));
+    assert!(result.is_ok());
+    assert!(lines.contains(&"marcador.txt".to_string()));
+}
+
+#[test]
+fn test_shell_args_are_not_interpreted() {
+    let (result, lines) = run_exec(
+        "command = \"printf '%s|'\"\nshell = true\nargs = [\"a   b\", \"c;echo INJETADO\", \"$HOME\"]\n",
+    );
+    assert!(result.is_ok());
+    assert_eq!(lines.last().map(|line| line.as_str()), Some("a   b|c;echo INJETADO|$HOME|"));
+    assert!(!lines.contains(&"INJETADO".to_string()));
+
+    // Sem args o comando é executado como está
+    let (result, lines) = run_exec("command = \"echo a && echo b\"\nshell = true\n");
+    assert!(result.is_ok());
+    assert!(lines.ends_with(&["a".to_string(), "b".to_string()]));
+}
+
+#[test]
+fn test_item_types_are_validated() {
+    let config = Config::parse(
+        "[exec]\ncommand = \"true\"\nargs = [\"a\", [\"b\"]]\nenv = { A = { B = 1 } }\nexit_codes = [0, \"1\"]\n",
+        "tasks.toml",
+    )
+    .unwrap();
+
+    let errors = ExecTask::new().resolve_params(config.find_task("exec").unwrap()).unwrap_err();
+    let mut items: Vec<(String, String)> = errors
+        .0
+        .iter()
+        .map(|error| match &error.kind {
+            ParamErrorKind::ItemTypeMismatch { item, .. } => (error.param.clone(), item.clone()),
+            other => panic!("esperado ItemTypeMismatch, obtido {:?}", other),
+        })
+        .collect();
+    items.sort();
+
+    assert_eq!(
+        items,
+        vec![
+            ("args".to_string(), "[1]".to_string()),
+            ("env".to_string(), ".A".to_string()),
+            ("exit_codes".to_string(), "[1]".to_string()),
+        ]
+    );
+}
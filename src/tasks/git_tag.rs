@@ -1,13 +1,128 @@
 use crate::errors::AppError;
+use crate::output::task_println;
 use crate::params::{ParamDefinition, ParamType, TaskParams};
+use crate::subprocess;
 use crate::task::Task;
+use std::process::Command;
 
 pub struct GitTagTask;
 
+/// Opções de criação da tag, extraídas dos parâmetros da task
+struct TagOptions {
+    tag: String,
+    message: Option<String>,
+    force: bool,
+    push: bool,
+    remote: String,
+    path: String,
+}
+
 impl GitTagTask {
     pub fn new() -> Self {
         Self
     }
+
+    /// Monta um comando `git` executado no repositório `path`
+    fn git(path: &str) -> Command {
+        let mut cmd = Command::new("git");
+        cmd.arg("-C").arg(path);
+        cmd
+    }
+
+    /// Commit para o qual a referência aponta, ou `None` se ela não existir
+    fn resolve_commit(path: &str, reference: &str) -> Result<Option<String>, AppError> {
+        let output = subprocess::output(
+            Self::git(path)
+                .args(["rev-parse", "--quiet", "--verify"])
+                .arg(format!("{}^{{commit}}", reference)),
+        )?;
+
+        Ok(output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string()))
+    }
+
+    /// Cria a tag (anotada se houver mensagem) e faz push se solicitado
+    fn create_tag(options: &TagOptions) -> Result<(), AppError> {
+        let tag_ref = format!("refs/tags/{}", options.tag);
+        let existing = if options.force {
+            None
+        } else {
+            Self::resolve_commit(&options.path, &tag_ref)?
+        };
+
+        match existing {
+            None => {
+                Self::run_tag(options)?;
+                task_println!("✅ Tag '{}' criada", options.tag);
+            }
+            // Uma nova tentativa depois de um push que falhou encontra a tag já criada
+            Some(commit) if Self::is_configured_tag(options, &tag_ref, &commit)? => {
+                task_println!("ℹ️  Tag '{}' já existe no commit atual", options.tag);
+            }
+            Some(_) => {
+                return Err(AppError::Generic(format!(
+                    "Tag '{}' já existe. Use force = true para sobrescrevê-la",
+                    options.tag
+                )));
+            }
+        }
+
+        if options.push {
+            let mut cmd = Self::git(&options.path);
+            cmd.arg("push");
+
+            if options.force {
+                cmd.arg("--force");
+            }
+
+            cmd.arg(&options.remote).arg(&tag_ref);
+            subprocess::run(&mut cmd)?;
+
+            task_println!("🚀 Push da tag '{}' para '{}' concluído", options.tag, options.remote);
+        }
+
+        Ok(())
+    }
+
+    /// Se a tag existente (apontando para `commit`) é exatamente a que seria criada:
+    /// no commit atual, do mesmo tipo (leve ou anotada) e com a mesma mensagem
+    fn is_configured_tag(options: &TagOptions, tag_ref: &str, commit: &str) -> Result<bool, AppError> {
+        if Self::resolve_commit(&options.path, "HEAD")?.as_deref() != Some(commit) {
+            return Ok(false);
+        }
+
+        let object_type = subprocess::run(Self::git(&options.path).args(["cat-file", "-t", tag_ref]))?;
+        let message = match object_type.trim() {
+            "tag" => Some(subprocess::run(
+                Self::git(&options.path).args(["for-each-ref", "--format=%(contents)", tag_ref]),
+            )?),
+            _ => None,
+        };
+
+        Ok(message.as_deref().map(str::trim) == options.message.as_deref().map(str::trim))
+    }
+
+    /// Executa `git tag`
+    fn run_tag(options: &TagOptions) -> Result<(), AppError> {
+        let mut cmd = Self::git(&options.path);
+        cmd.arg("tag");
+
+        if options.force {
+            cmd.arg("--force");
+        }
+
+        // Com mensagem a tag é anotada; sem mensagem, leve
+        if let Some(message) = &options.message {
+            cmd.arg("--annotate").arg("--message").arg(message);
+        }
+
+        cmd.arg(&options.tag);
+        subprocess::run(&mut cmd)?;
+
+        Ok(())
+    }
 }
 
 impl Task for GitTagTask {
@@ -24,42 +139,50 @@ impl Task for GitTagTask {
             ParamDefinition::new("tag", ParamType::String)
                 .required()
                 .description("Nome da tag a ser criada"),
-            
+
             ParamDefinition::new("message", ParamType::String)
                 .optional()
-                .description("Mensagem da tag (opcional)"),
-            
+                .description("Mensagem da tag (opcional). Se informada, cria uma tag anotada"),
+
             ParamDefinition::new("push", ParamType::Bool)
                 .default_bool(false)
                 .description("Se deve fazer push da tag para o remote"),
-            
+
             ParamDefinition::new("remote", ParamType::String)
                 .default_str("origin")
                 .description("Nome do remote para push (default: origin)"),
+
+            ParamDefinition::new("force", ParamType::Bool)
+                .default_bool(false)
+                .description("Sobrescreve a tag se ela já existir"),
+
+            ParamDefinition::new("path", ParamType::String)
+                .default_str(".")
+                .description("Diretório do repositório Git (default: diretório atual)"),
         ]
     }
 
     fn run(&self, params: &TaskParams) -> Result<(), AppError> {
-        let tag = params.get_string("tag")
-            .ok_or_else(|| AppError::Generic("Tag é obrigatória".to_string()))?;
-        
-        let message = params.get_string("message");
-        let push = params.get_bool_or("push", false);
-        let remote = params.get_string_or("remote", "origin");
-
-        println!("🏷️  Criando tag Git: {}", tag);
-        
-        if let Some(msg) = message {
-            println!("📝 Mensagem: {}", msg);
-        }
-        
-        if push {
-            println!("🚀 Push para remote '{}': {}", remote, push);
+        let tag = params.require_string("tag")?;
+
+        let options = TagOptions {
+            tag,
+            message: params.get_string("message"),
+            force: params.get_bool_or("force", false),
+            push: params.get_bool_or("push", false),
+            remote: params.get_string_or("remote", "origin"),
+            path: params.get_string_or("path", "."),
+        };
+
+        task_println!("🏷️  Criando tag Git: {}", options.tag);
+
+        if let Some(msg) = &options.message {
+            task_println!("📝 Mensagem: {}", msg);
         }
 
-        // Aqui viria a implementação real do git tag
-        println!("✅ Tag criada com sucesso!");
-        
-        Ok(())
+        Self::create_tag(&options)
     }
 }
+
+#[cfg(test)]
+mod tests;
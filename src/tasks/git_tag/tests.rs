@@ -0,0 +1,136 @@
+use super::*;
+use crate::test_utils::TempDir;
+use std::{fs, path::Path};
+
+fn git(path: &str, args: &[&str]) -> String {
+    subprocess::run(GitTagTask::git(path).args(args)).unwrap()
+}
+
+/// Cria um repositório com um commit e um remote `origin` apontando para um repositório bare
+fn setup_repository(dir: &TempDir) -> (String, String) {
+    let repo = dir.join("repo");
+    let remote = dir.join("remote.git");
+
+    fs::create_dir_all(&repo).unwrap();
+    git(&repo, &["init", "--quiet"]);
+    git(&repo, &["config", "user.name", "Runner"]);
+    git(&repo, &["config", "user.email", "runner@example.com"]);
+    git(&repo, &["commit", "--quiet", "--allow-empty", "--message", "inicial"]);
+
+    git(&repo, &["init", "--quiet", "--bare", &remote]);
+    git(&repo, &["remote", "add", "origin", &remote]);
+
+    (repo, remote)
+}
+
+fn options(path: &str, tag: &str) -> TagOptions {
+    TagOptions {
+        tag: tag.to_string(),
+        message: None,
+        force: false,
+        push: false,
+        remote: "origin".to_string(),
+        path: path.to_string(),
+    }
+}
+
+#[test]
+fn test_lightweight_tag() {
+    let dir = TempDir::new();
+    let (repo, _) = setup_repository(&dir);
+
+    GitTagTask::create_tag(&options(&repo, "v1.0.0")).unwrap();
+
+    assert_eq!(git(&repo, &["cat-file", "-t", "v1.0.0"]).trim(), "commit");
+}
+
+#[test]
+fn test_annotated_tag() {
+    let dir = TempDir::new();
+    let (repo, _) = setup_repository(&dir);
+
+    let mut opts = options(&repo, "v1.0.0");
+    opts.message = Some("Versão 1.0.0".to_string());
+    GitTagTask::create_tag(&opts).unwrap();
+
+    assert_eq!(git(&repo, &["cat-file", "-t", "v1.0.0"]).trim(), "tag");
+    assert_eq!(
+        git(&repo, &["tag", "-l", "--format=%(contents:subject)", "v1.0.0"]).trim(),
+        "Versão 1.0.0"
+    );
+}
+
+#[test]
+fn test_existing_tag_requires_force() {
+    let dir = TempDir::new();
+    let (repo, _) = setup_repository(&dir);
+
+    GitTagTask::create_tag(&options(&repo, "v1.0.0")).unwrap();
+    git(&repo, &["commit", "--quiet", "--allow-empty", "--message", "segundo"]);
+
+    assert!(GitTagTask::create_tag(&options(&repo, "v1.0.0")).is_err());
+
+    let mut opts = options(&repo, "v1.0.0");
+    opts.force = true;
+    GitTagTask::create_tag(&opts).unwrap();
+
+    assert_eq!(
+        git(&repo, &["rev-parse", "v1.0.0"]),
+        git(&repo, &["rev-parse", "HEAD"])
+    );
+}
+
+#[test]
+fn test_existing_identical_tag_is_kept() {
+    let dir = TempDir::new();
+    let (repo, _) = setup_repository(&dir);
+
+    // Uma nova tentativa encontra a tag igual à configurada no commit atual
+    GitTagTask::create_tag(&options(&repo, "v1.0.0")).unwrap();
+    GitTagTask::create_tag(&options(&repo, "v1.0.0")).unwrap();
+
+    // Uma tag leve não satisfaz uma configuração com mensagem
+    let mut annotated = options(&repo, "v1.0.0");
+    annotated.message = Some("Versão 1.0.0".to_string());
+    assert!(GitTagTask::create_tag(&annotated).is_err());
+
+    annotated.tag = "v2.0.0".to_string();
+    GitTagTask::create_tag(&annotated).unwrap();
+    GitTagTask::create_tag(&annotated).unwrap();
+
+    annotated.message = Some("Outra mensagem".to_string());
+    assert!(GitTagTask::create_tag(&annotated).is_err());
+    assert!(GitTagTask::create_tag(&options(&repo, "v2.0.0")).is_err());
+}
+
+#[test]
+fn test_push_to_remote() {
+    let dir = TempDir::new();
+    let (repo, remote) = setup_repository(&dir);
+
+    let mut opts = options(&repo, "v1.0.0");
+    opts.push = true;
+    GitTagTask::create_tag(&opts).unwrap();
+
+    assert_eq!(
+        git(&remote, &["rev-parse", "refs/tags/v1.0.0"]),
+        git(&repo, &["rev-parse", "HEAD"])
+    );
+}
+
+#[test]
+fn test_git_stderr_is_reported() {
+    let dir = TempDir::new();
+    let (repo, _) = setup_repository(&dir);
+
+    let mut opts = options(&repo, "v1.0.0");
+    opts.push = true;
+    opts.remote = "inexistente".to_string();
+
+    match GitTagTask::create_tag(&opts) {
+        Err(AppError::CommandFailed { stderr, .. }) => assert!(stderr.contains("inexistente")),
+        other => panic!("esperado erro do git, obtido {:?}", other),
+    }
+
+    assert!(!Path::new(&dir.join("remote.git")).join("refs/tags/v1.0.0").exists());
+}
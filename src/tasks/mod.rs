@@ -1,3 +1,5 @@
+pub mod build;
+pub mod exec;
 pub mod git_tag;
 
 use crate::task::Task;
@@ -11,9 +13,17 @@ pub struct TaskDescriptor {
 /// Retorna todas as tasks disponíveis no sistema
 pub fn available_tasks() -> Vec<TaskDescriptor> {
     vec![
+        TaskDescriptor {
+            name: "build",
+            factory: || Box::new(build::BuildTask::new()),
+        },
+        TaskDescriptor {
+            name: "exec",
+            factory: || Box::new(exec::ExecTask::new()),
+        },
         TaskDescriptor {
             name: "git-tag",
             factory: || Box::new(git_tag::GitTagTask::new()),
-        }
+        },
     ]
 }